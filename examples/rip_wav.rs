@@ -0,0 +1,14 @@
+//! Rips every audio track on the disc in `/dev/sr0` to its own WAV file.
+
+use std::path::Path;
+
+use cd_read::CDRom;
+
+fn main() {
+    let mut cd_rom = CDRom::new().expect("Could not open the drive");
+
+    let paths = cd_rom.rip_all_tracks(Path::new(".")).expect("Could not rip the disc");
+    for path in paths {
+        println!("Ripped {}", path.display());
+    }
+}
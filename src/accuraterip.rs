@@ -0,0 +1,260 @@
+//! Streaming calculation of AccurateRip checksums.
+//!
+//! Samples from [`crate::CDRom::read_audio`] are fed in as they are read, and
+//! the final checksum can then be compared against the AccurateRip database, which
+//! is looked up with the [`DiscIds`] of the disc.
+
+use crate::constants;
+
+const SAMPLES_PER_FRAME: usize = constants::CD_SAMPLES_PER_FRAME as usize;
+
+/// Samples skipped at the start of the first track, 5 frames minus 1 sample
+const FIRST_TRACK_SKIP: usize = SAMPLES_PER_FRAME * 5 - 1;
+
+/// Samples skipped at the end of the last track, 5 frames
+const LAST_TRACK_SKIP: usize = SAMPLES_PER_FRAME * 5;
+
+/// Tracks the position within a track and which samples are checksummed.
+#[derive(Debug, Clone)]
+struct Accumulator {
+    /// Number of stereo samples seen so far
+    position: usize,
+    /// First sample position included in the checksum
+    start: usize,
+    /// Position past the last sample included in the checksum
+    end: usize,
+    /// Left channel of a stereo sample split across calls to `update`
+    pending: Option<i16>,
+}
+
+impl Accumulator {
+    fn new(frames: u32, first_track: bool, last_track: bool) -> Self {
+        let total = frames as usize * SAMPLES_PER_FRAME;
+
+        Self {
+            position: 0,
+            start: if first_track { FIRST_TRACK_SKIP } else { 0 },
+            end: if last_track { total.saturating_sub(LAST_TRACK_SKIP) } else { total },
+            pending: None,
+        }
+    }
+
+    /// Call `f` with the multiplier and value of every sample that counts towards the checksum.
+    fn update(&mut self, samples: &[i16], mut f: impl FnMut(u32, u32)) {
+        let mut samples = samples;
+
+        if let Some(left) = self.pending.take() {
+            let Some((right, rest)) = samples.split_first() else {
+                self.pending = Some(left);
+                return
+            };
+
+            self.push(left, *right, &mut f);
+            samples = rest;
+        }
+
+        let mut pairs = samples.chunks_exact(2);
+        for pair in &mut pairs {
+            self.push(pair[0], pair[1], &mut f);
+        }
+
+        if let [left] = pairs.remainder() {
+            self.pending = Some(*left);
+        }
+    }
+
+    fn push(&mut self, left: i16, right: i16, f: &mut impl FnMut(u32, u32)) {
+        if self.position >= self.start && self.position < self.end {
+            let value = (left as u16 as u32) | ((right as u16 as u32) << 16);
+            f(self.position as u32 + 1, value);
+        }
+
+        self.position += 1;
+    }
+}
+
+/// Accumulator for the original AccurateRip checksum.
+#[derive(Debug, Clone)]
+pub struct AccurateRipV1 {
+    accumulator: Accumulator,
+    checksum: u32,
+}
+
+impl AccurateRipV1 {
+    /// Create an accumulator for a track that is `frames` long.
+    ///
+    /// The first and last tracks of a disc have some samples excluded from
+    /// the checksum, because drive read offsets make them unreliable.
+    pub fn new(frames: u32, first_track: bool, last_track: bool) -> Self {
+        Self {
+            accumulator: Accumulator::new(frames, first_track, last_track),
+            checksum: 0,
+        }
+    }
+
+    /// Feed interleaved stereo samples into the checksum, in the order they were read.
+    pub fn update(&mut self, samples: &[i16]) {
+        let checksum = &mut self.checksum;
+
+        self.accumulator.update(samples, |multiplier, value| {
+            *checksum = checksum.wrapping_add(multiplier.wrapping_mul(value));
+        });
+    }
+
+    /// The checksum of all samples fed in so far.
+    pub fn checksum(&self) -> u32 {
+        self.checksum
+    }
+}
+
+/// Accumulator for the AccurateRip v2 checksum, which unlike v1 includes the
+/// high bits of each product so no sample is ignored.
+#[derive(Debug, Clone)]
+pub struct AccurateRipV2 {
+    accumulator: Accumulator,
+    checksum: u32,
+}
+
+impl AccurateRipV2 {
+    /// Create an accumulator for a track that is `frames` long.
+    ///
+    /// The first and last tracks of a disc have some samples excluded from
+    /// the checksum, because drive read offsets make them unreliable.
+    pub fn new(frames: u32, first_track: bool, last_track: bool) -> Self {
+        Self {
+            accumulator: Accumulator::new(frames, first_track, last_track),
+            checksum: 0,
+        }
+    }
+
+    /// Feed interleaved stereo samples into the checksum, in the order they were read.
+    pub fn update(&mut self, samples: &[i16]) {
+        let checksum = &mut self.checksum;
+
+        self.accumulator.update(samples, |multiplier, value| {
+            let product = multiplier as u64 * value as u64;
+            *checksum = checksum
+                .wrapping_add(product as u32)
+                .wrapping_add((product >> 32) as u32);
+        });
+    }
+
+    /// The checksum of all samples fed in so far.
+    pub fn checksum(&self) -> u32 {
+        self.checksum
+    }
+}
+
+/// The IDs that identify a disc in the AccurateRip database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiscIds {
+    /// Sum of the track offsets and the lead-out
+    pub id1: u32,
+    /// Sum of the track offsets weighted by their track number
+    pub id2: u32,
+    /// The FreeDB (CDDB) ID of the disc
+    pub cddb: u32,
+}
+
+impl DiscIds {
+    /// Compute the IDs from the LBA of the lead-out and of each audio track, starting
+    /// from track 1.
+    pub fn new(leadout: i32, offsets: &[i32]) -> Self {
+        let mut id1 = leadout as u32;
+        let mut id2 = (leadout as u32).wrapping_mul(offsets.len() as u32 + 1);
+        for (i, offset) in offsets.iter().enumerate() {
+            id1 = id1.wrapping_add(*offset as u32);
+            id2 = id2.wrapping_add((*offset).max(1) as u32 * (i as u32 + 1));
+        }
+
+        // The CDDB ID works in whole seconds from the start of the pregap
+        let seconds = |lba: i32| ((lba + constants::CD_MSF_OFFSET) / constants::CD_FRAMES) as u32;
+        let digit_sum = |mut n: u32| {
+            let mut sum = 0;
+            while n > 0 {
+                sum += n % 10;
+                n /= 10;
+            }
+            sum
+        };
+
+        let checksum = offsets.iter().map(|o| digit_sum(seconds(*o))).sum::<u32>() % 255;
+        let length = seconds(leadout) - offsets.first().map_or(0, |o| seconds(*o));
+        let cddb = (checksum << 24) | (length << 8) | offsets.len() as u32;
+
+        Self { id1, id2, cddb }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Length of the synthetic track
+    const FRAMES: u32 = 10;
+    const SAMPLES: u32 = FRAMES * SAMPLES_PER_FRAME as u32;
+
+    fn checksums(left: i16, right: i16, first_track: bool, last_track: bool) -> (u32, u32) {
+        let samples: Vec<i16> = [left, right].repeat(SAMPLES as usize);
+
+        let mut v1 = AccurateRipV1::new(FRAMES, first_track, last_track);
+        let mut v2 = AccurateRipV2::new(FRAMES, first_track, last_track);
+        v1.update(&samples);
+        v2.update(&samples);
+
+        (v1.checksum(), v2.checksum())
+    }
+
+    /// Sum of the multipliers from `first` to `last`, inclusive.
+    fn sum(first: u32, last: u32) -> u32 {
+        (first..=last).sum()
+    }
+
+    #[test]
+    fn middle_track_counts_every_sample() {
+        // With a value of 1 the checksum is the sum of the multipliers 1 to 5880
+        assert_eq!(checksums(1, 0, false, false), (17_290_140, 17_290_140));
+    }
+
+    #[test]
+    fn v2_adds_the_high_bits() {
+        // k * 0xFFFFFFFF is k << 32 minus k, so v1 keeps -k and v2 adds back k - 1
+        assert_eq!(checksums(-1, -1, false, false), (17_290_140u32.wrapping_neg(), SAMPLES.wrapping_neg()));
+    }
+
+    #[test]
+    fn first_and_last_tracks_skip_five_frames() {
+        // The first track skips 5 frames less one sample, the last track 5 frames
+        assert_eq!(checksums(1, 0, true, false).0, sum(2940, SAMPLES));
+        assert_eq!(checksums(1, 0, false, true).0, sum(1, SAMPLES - 2940));
+        // On a one track disc only the sample at 2940 is left of ten frames
+        assert_eq!(checksums(1, 0, true, true), (2940, 2940));
+    }
+
+    #[test]
+    fn samples_can_be_fed_in_any_pieces() {
+        let samples: Vec<i16> = (0..SAMPLES as i32 * 2).map(|i| (i * 7919) as i16).collect();
+
+        let mut whole = AccurateRipV2::new(FRAMES, true, true);
+        whole.update(&samples);
+
+        let mut pieces = AccurateRipV2::new(FRAMES, true, true);
+        for piece in samples.chunks(333) {
+            pieces.update(piece);
+        }
+
+        assert_eq!(pieces.checksum(), whole.checksum());
+    }
+
+    #[test]
+    fn disc_ids_of_the_libdiscid_test_disc() {
+        let offsets = [0, 18751, 39588, 59407, 79002, 99976, 124683, 147128, 166186, 182410];
+        let ids = DiscIds::new(206385, &offsets);
+
+        // The CDDB ID is the one libdiscid's tests expect
+        assert_eq!(ids.cddb, 0x830abf0a);
+        assert_eq!(ids.id1, 1_123_516);
+        // Track 1 at LBA 0 counts as 1
+        assert_eq!(ids.id2, 9_028_575);
+    }
+}
@@ -0,0 +1,86 @@
+//! An async wrapper around [`CDRom`], for use with the tokio runtime.
+//!
+//! ioctls block until the drive responds, which can take seconds while a disc
+//! spins up, so every operation is run on tokio's blocking thread pool.
+
+use std::panic;
+use std::sync::{Arc, Mutex};
+
+use crate::constants::{self, AddressType, DiscType, Status};
+use crate::structures::{Addr, TocEntry, Track};
+use crate::{CDRom, CDRomError};
+
+/// A [`CDRom`] whose operations can be awaited without blocking the runtime.
+///
+/// Operations are serialized, so only one ioctl is in flight at a time, even when
+/// the wrapper is cloned and shared between tasks.
+#[derive(Clone)]
+pub struct AsyncCDRom {
+    drive: Arc<Mutex<CDRom>>,
+}
+
+impl AsyncCDRom {
+    pub fn new(drive: CDRom) -> Self {
+        Self {
+            drive: Arc::new(Mutex::new(drive)),
+        }
+    }
+
+    /// Run `f` with exclusive access to the drive on the blocking thread pool.
+    ///
+    /// This can be used for any operation that doesn't have an async version here.
+    pub async fn run<T, F>(&self, f: F) -> T
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut CDRom) -> T + Send + 'static,
+    {
+        let drive = Arc::clone(&self.drive);
+
+        let result = tokio::task::spawn_blocking(move || {
+            // A panic in an earlier operation doesn't leave the drive itself in a bad state
+            let mut drive = drive.lock().unwrap_or_else(|e| e.into_inner());
+            f(&mut drive)
+        })
+        .await;
+
+        match result {
+            Ok(v) => v,
+            Err(e) => panic::resume_unwind(e.into_panic()),
+        }
+    }
+
+    /// See [`CDRom::status`].
+    pub async fn status(&self) -> Result<Status, CDRomError> {
+        self.run(|d| d.status()).await
+    }
+
+    /// See [`CDRom::disc_type`].
+    pub async fn disc_type(&self) -> Result<Option<DiscType>, CDRomError> {
+        self.run(|d| d.disc_type()).await
+    }
+
+    /// See [`CDRom::toc`].
+    pub async fn toc(&self, address_type: AddressType) -> Result<Vec<TocEntry>, CDRomError> {
+        self.run(move |d| d.toc(address_type)).await
+    }
+
+    /// See [`CDRom::tracks`].
+    pub async fn tracks(&self) -> Result<Vec<Track>, CDRomError> {
+        self.run(|d| d.tracks()).await
+    }
+
+    /// Read any number of frames of audio, like [`CDRom::read_audio_range`].
+    pub async fn read_audio(&self, start: Addr, frames: usize) -> Result<Vec<i16>, CDRomError> {
+        self.run(move |d| {
+            let mut samples = vec![0i16; frames * constants::CD_FRAMESIZE_RAW as usize / 2];
+            d.read_audio_range(start, frames, &mut samples)?;
+            Ok(samples)
+        })
+        .await
+    }
+
+    /// See [`CDRom::eject`].
+    pub async fn eject(&self) -> Result<(), CDRomError> {
+        self.run(|d| d.eject()).await
+    }
+}
@@ -0,0 +1,251 @@
+//! The layer every ioctl goes through, so a [`CDRom`](crate::CDRom) can be backed
+//! by something other than a real drive.
+
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::slice;
+
+use nix::errno::Errno;
+use nix::libc;
+use num_traits::FromPrimitive as _;
+
+use crate::constants::{self, op_to_ioctl, AddressType, AudioStates, DiscType, Operation, Status};
+use crate::packet_commands::{GenericCommand, GenericPacketCommand};
+use crate::structures::{Msf, ReadAudio, TocHeader, _SubChannel, _TocEntry};
+
+/// Issues ioctls on behalf of a [`CDRom`](crate::CDRom).
+pub trait IoctlBackend: Send {
+    /// Issue the ioctl `request` with the argument `arg`, which is either an integer
+    /// or a pointer depending on the request.
+    ///
+    /// # Safety
+    /// When the request takes a pointer, `arg` must point to valid memory of the type
+    /// the request expects.
+    unsafe fn ioctl(&mut self, request: u64, arg: libc::c_ulong) -> nix::Result<i32>;
+}
+
+/// Issues ioctls on an open device file, which is closed when the backend is dropped.
+#[derive(Debug)]
+pub struct SystemBackend {
+    fd: OwnedFd,
+}
+
+impl SystemBackend {
+    pub fn new(fd: OwnedFd) -> Self {
+        Self { fd }
+    }
+}
+
+impl IoctlBackend for SystemBackend {
+    unsafe fn ioctl(&mut self, request: u64, arg: libc::c_ulong) -> nix::Result<i32> {
+        Errno::result(libc::ioctl(self.fd.as_raw_fd(), request as _, arg))
+    }
+}
+
+/// A fake drive that answers ioctls from canned data, for testing without hardware.
+///
+/// Only the drive and disc status, door locking, table of contents, subchannel, and audio
+/// reads are answered, along with the PREVENT ALLOW MEDIUM REMOVAL packet command. Every
+/// other request fails with `ENOSYS`, which turns into
+/// [`CDRomError::Unsupported`](crate::CDRomError::Unsupported).
+#[derive(Debug, Clone)]
+pub struct MockBackend {
+    pub status: Status,
+    pub disc_type: DiscType,
+    pub first_track: u8,
+    /// Start LBA and control nibble of each track, starting from `first_track`
+    pub tracks: Vec<(i32, u8)>,
+    pub leadout: i32,
+    /// Interleaved stereo samples for the whole disc, starting at LBA 0
+    pub audio: Vec<i16>,
+    /// LBA reported as the current position by subchannel reads
+    pub position: i32,
+    /// Sectors that fail with `EIO` when read
+    pub bad_sectors: Vec<i32>,
+    /// Whether the door can be locked with the kernel ioctl, which otherwise fails with
+    /// `EOPNOTSUPP` like it does for drives the kernel can't lock
+    pub can_lock: bool,
+}
+
+impl Default for MockBackend {
+    fn default() -> Self {
+        Self {
+            status: Status::DiscOK,
+            disc_type: DiscType::Audio,
+            first_track: 1,
+            tracks: Vec::new(),
+            leadout: 0,
+            audio: Vec::new(),
+            position: 0,
+            bad_sectors: Vec::new(),
+            can_lock: true,
+        }
+    }
+}
+
+impl MockBackend {
+    /// Start LBA and control nibble of a track, including the lead-out.
+    fn track(&self, track: u8) -> Option<(i32, u8)> {
+        if track == constants::CDROM_LEADOUT {
+            return Some((self.leadout, self.tracks.last().map_or(0, |t| t.1)))
+        }
+
+        let index = track.checked_sub(self.first_track)?;
+        self.tracks.get(index as usize).copied()
+    }
+
+    /// Track containing `lba` and the index of it in `tracks`.
+    fn track_at(&self, lba: i32) -> Option<usize> {
+        self.tracks.iter().rposition(|t| t.0 <= lba)
+    }
+}
+
+impl IoctlBackend for MockBackend {
+    unsafe fn ioctl(&mut self, request: u64, arg: libc::c_ulong) -> nix::Result<i32> {
+        let Some(op) = Operation::from_u8(request as u8).filter(|op| op_to_ioctl(*op) == request) else {
+            return Err(Errno::ENOTTY)
+        };
+
+        match op {
+            Operation::DriveStatus => return Ok(self.status as i32),
+            Operation::MediaChanged => return Ok(0),
+            Operation::LockDoor if self.can_lock => return Ok(0),
+            Operation::LockDoor => return Err(Errno::EOPNOTSUPP),
+            Operation::SendPacket => {
+                let cmd = &mut *(arg as *mut GenericCommand);
+                if cmd.cdb[0] == GenericPacketCommand::PreventAllowMediumRemoval as u8 {
+                    cmd.stat = 0;
+                    return Ok(0)
+                }
+            },
+            _ => (),
+        }
+
+        if self.status != Status::DiscOK {
+            return Err(Errno::ENOMEDIUM)
+        }
+
+        match op {
+            Operation::DiscStatus => Ok(self.disc_type as i32),
+            Operation::ReadTocHeader => {
+                let header = &mut *(arg as *mut TocHeader);
+                header.first_track = self.first_track;
+                header.last_track = self.first_track + self.tracks.len().saturating_sub(1) as u8;
+
+                Ok(0)
+            },
+            Operation::ReadTocEntry => {
+                let entry = &mut *(arg as *mut _TocEntry);
+                let (lba, ctrl) = self.track(entry.track).ok_or(Errno::EINVAL)?;
+
+                entry.adr_ctrl = 0x10 | ctrl;
+                entry.datamode = (ctrl & 0x04 != 0) as u8;
+                if entry.format == AddressType::Msf as u8 {
+                    entry.addr.msf = Msf::from_lba(lba);
+                } else {
+                    entry.addr.lba = lba;
+                }
+
+                Ok(0)
+            },
+            Operation::SubChannel => {
+                let sub = &mut *(arg as *mut _SubChannel);
+                let index = self.track_at(self.position);
+                let (start, ctrl) = index.map_or((0, 0), |i| self.tracks[i]);
+
+                sub.audiostatus = AudioStates::NoStatus as u8;
+                sub.adr_ctrl = 0x10 | ctrl;
+                sub.trk = index.map_or(0, |i| self.first_track + i as u8);
+                sub.ind = 1;
+                if sub.format == AddressType::Msf as u8 {
+                    sub.absaddr.msf = Msf::from_lba(self.position);
+                    sub.reladdr.msf = Msf::from_lba(self.position - start - constants::CD_MSF_OFFSET);
+                } else {
+                    sub.absaddr.lba = self.position;
+                    sub.reladdr.lba = self.position - start;
+                }
+
+                Ok(0)
+            },
+            Operation::ReadAudio => {
+                let ra = &*(arg as *const ReadAudio);
+                let lba = match ra.addr_format {
+                    AddressType::Lba => ra.addr.lba,
+                    AddressType::Msf => ra.addr.msf.to_lba(),
+                };
+
+                if self.bad_sectors.iter().any(|s| (lba..lba + ra.nframes).contains(s)) {
+                    return Err(Errno::EIO)
+                }
+
+                let frame_values = constants::CD_FRAMESIZE_RAW as usize / 2;
+                let start = usize::try_from(lba).map_err(|_| Errno::EINVAL)? * frame_values;
+                let len = ra.nframes as usize * frame_values;
+                let samples = self.audio.get(start..start + len).ok_or(Errno::EIO)?;
+
+                slice::from_raw_parts_mut(ra.buf, len).copy_from_slice(samples);
+
+                Ok(0)
+            },
+            _ => Err(Errno::ENOSYS),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::Addr;
+    use crate::{CDRom, CDRomError};
+
+    fn three_tracks() -> MockBackend {
+        MockBackend {
+            tracks: vec![(0, 0x00), (1000, 0x00), (2500, 0x04)],
+            leadout: 4000,
+            disc_type: DiscType::Mixed,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn tracks_end_at_the_next_track_or_the_leadout() {
+        let mut drive = CDRom::with_backend(three_tracks());
+        let tracks = drive.tracks().unwrap();
+
+        assert_eq!(tracks.len(), 3);
+        assert_eq!(tracks.iter().map(|t| t.number).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(tracks.iter().map(|t| t.start).collect::<Vec<_>>(), [Addr::Lba(0), Addr::Lba(1000), Addr::Lba(2500)]);
+        assert_eq!(tracks[0].length_frames, 1000);
+        assert_eq!(tracks[1].length_frames, 1500);
+        // The last track runs up to the lead-out
+        assert_eq!(tracks[2].length_frames, 1500);
+        assert!(tracks[0].is_audio && tracks[1].is_audio && !tracks[2].is_audio);
+    }
+
+    #[test]
+    fn no_medium_is_no_disc() {
+        let mut drive = CDRom::with_backend(MockBackend {
+            status: Status::NoDisc,
+            ..three_tracks()
+        });
+
+        assert!(matches!(drive.tracks(), Err(CDRomError::NoDisc)));
+        assert!(matches!(drive.toc_header(), Err(CDRomError::NoDisc)));
+    }
+
+    #[test]
+    fn bad_sector_is_read_error_at_the_address() {
+        let frame_values = constants::CD_FRAMESIZE_RAW as usize / 2;
+        let mut drive = CDRom::with_backend(MockBackend {
+            audio: vec![0; 20 * frame_values],
+            bad_sectors: vec![12],
+            ..three_tracks()
+        });
+
+        let mut buf = vec![0i16; 5 * frame_values];
+        assert!(drive.read_audio_into(Addr::Lba(0), 5, &mut buf).is_ok());
+        assert!(matches!(
+            drive.read_audio_into(Addr::Lba(10), 5, &mut buf),
+            Err(CDRomError::ReadError { address: Some(Addr::Lba(10)) })
+        ));
+    }
+}
@@ -0,0 +1,167 @@
+//! Parsing of CD-TEXT data, as stored in the lead-in of many commercial audio CDs.
+//!
+//! CD-TEXT is made up of 18 byte packs, each carrying 12 bytes of text for a
+//! given pack type. Strings are null terminated and may span several packs.
+
+/// Size of a single CD-TEXT pack
+const PACK_SIZE: usize = 18;
+
+const PACK_TITLE: u8 = 0x80;
+const PACK_PERFORMER: u8 = 0x81;
+const PACK_SONGWRITER: u8 = 0x82;
+const PACK_COMPOSER: u8 = 0x83;
+
+/// CD-TEXT information for a whole disc.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CdText {
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub songwriter: Option<String>,
+    pub composer: Option<String>,
+    pub tracks: Vec<TrackText>,
+}
+
+/// CD-TEXT information for a single track.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrackText {
+    pub number: u8,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub songwriter: Option<String>,
+    pub composer: Option<String>,
+}
+
+impl CdText {
+    /// Parse the response to a READ TOC/PMA/ATIP command with format 5,
+    /// including the 4 byte header.
+    ///
+    /// Only the first language block is read. Returns `None` if there is no
+    /// text in the response.
+    pub fn from_packs(data: &[u8]) -> Option<Self> {
+        let packs = data.get(4..)?;
+
+        let mut cd_text = CdText::default();
+        let mut found = false;
+
+        for pack_type in [PACK_TITLE, PACK_PERFORMER, PACK_SONGWRITER, PACK_COMPOSER] {
+            let mut first_track = None;
+            let mut double_byte = false;
+            let mut text = Vec::new();
+
+            for pack in packs.chunks_exact(PACK_SIZE) {
+                let block = (pack[3] >> 4) & 0x07;
+                if pack[0] != pack_type || block != 0 {
+                    continue
+                }
+
+                first_track.get_or_insert(pack[1] & 0x7F);
+                double_byte = pack[3] & 0x80 != 0;
+                text.extend_from_slice(&pack[4..16]);
+            }
+
+            let Some(first_track) = first_track else {
+                continue
+            };
+
+            let strings = split_strings(&text, double_byte);
+            for (i, string) in strings.into_iter().enumerate() {
+                let Some(string) = string else {
+                    continue
+                };
+                found = true;
+
+                let number = first_track as usize + i;
+                let field = if number == 0 {
+                    cd_text.field(pack_type)
+                } else {
+                    let Ok(number) = u8::try_from(number) else {
+                        break
+                    };
+
+                    let index = match cd_text.tracks.iter().position(|t| t.number == number) {
+                        Some(index) => index,
+                        None => {
+                            cd_text.tracks.push(TrackText { number, ..Default::default() });
+                            cd_text.tracks.len() - 1
+                        },
+                    };
+
+                    cd_text.tracks[index].field(pack_type)
+                };
+
+                *field = Some(string);
+            }
+        }
+
+        if !found {
+            return None
+        }
+
+        cd_text.tracks.sort_by_key(|t| t.number);
+        Some(cd_text)
+    }
+
+    fn field(&mut self, pack_type: u8) -> &mut Option<String> {
+        match pack_type {
+            PACK_TITLE => &mut self.title,
+            PACK_PERFORMER => &mut self.performer,
+            PACK_SONGWRITER => &mut self.songwriter,
+            _ => &mut self.composer,
+        }
+    }
+}
+
+impl TrackText {
+    fn field(&mut self, pack_type: u8) -> &mut Option<String> {
+        match pack_type {
+            PACK_TITLE => &mut self.title,
+            PACK_PERFORMER => &mut self.performer,
+            PACK_SONGWRITER => &mut self.songwriter,
+            _ => &mut self.composer,
+        }
+    }
+}
+
+/// Split concatenated pack text into its null terminated strings, decoding
+/// each one. A lone tab means the string is the same as the previous one.
+fn split_strings(text: &[u8], double_byte: bool) -> Vec<Option<String>> {
+    let char_size = if double_byte { 2 } else { 1 };
+
+    let mut strings = Vec::new();
+    let mut previous: Option<String> = None;
+    let mut start = 0;
+
+    for (i, c) in text.chunks_exact(char_size).enumerate() {
+        if c.iter().any(|b| *b != 0) {
+            continue
+        }
+
+        let raw = &text[start..i * char_size];
+        start = (i + 1) * char_size;
+
+        let string = if raw.iter().all(|b| *b == b'\t') && !raw.is_empty() {
+            previous.clone()
+        } else if raw.is_empty() {
+            None
+        } else {
+            Some(decode(raw, double_byte))
+        };
+
+        if string.is_some() {
+            previous.clone_from(&string);
+        }
+        strings.push(string);
+    }
+
+    strings
+}
+
+/// Decode a string as either ISO-8859-1 or MS-JIS.
+fn decode(raw: &[u8], double_byte: bool) -> String {
+    if double_byte {
+        let (string, _) = encoding_rs::SHIFT_JIS.decode_without_bom_handling(raw);
+        string.into_owned()
+    } else {
+        raw.iter().map(|b| *b as char).collect()
+    }
+}
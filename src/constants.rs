@@ -156,6 +156,13 @@ pub enum Status {
     DiscOK = 4
 }
 
+impl Status {
+    /// Whether there is a disc in the drive that is ready to be read.
+    pub fn is_ready(&self) -> bool {
+        *self == Status::DiscOK
+    }
+}
+
 /// Disc status possibilities returned by CDROM_DISC_STATUS ioctl
 #[derive(FromPrimitive, ToPrimitive)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -169,13 +176,28 @@ pub enum DiscType {
     Mixed = 105,
 }
 
+impl DiscType {
+    /// Whether the disc has audio tracks, including mixed mode discs.
+    pub fn is_audio(&self) -> bool {
+        matches!(self, DiscType::Audio | DiscType::Mixed)
+    }
+
+    /// Whether the disc has data tracks, including mixed mode discs.
+    pub fn is_data(&self) -> bool {
+        matches!(self, DiscType::Data1 | DiscType::Data2 | DiscType::XA21 | DiscType::XA22 | DiscType::Mixed)
+    }
+}
+
 pub const CD_MINS: i32 = 74;
 pub const CD_SECS: i32 = 60;
 pub const CD_FRAMES: i32 = 75;
 pub const CD_SYNC_SIZE: i32 = 12;
 pub const CD_MSF_OFFSET: i32 = 150;
+/// Offset of lead-in addresses, which MMC maps to MSF times from 90:00:00 upwards
+pub const CD_LEADIN_MSF_OFFSET: i32 = 450150;
 pub const CD_CHUNK_SIZE: i32 = 24;
 pub const CD_NUM_OF_CHUNKS: i32 = 98;
+/// Size of the raw P-W subchannel data of a frame
 pub const CD_FRAMESIZE_SUB: i32 = 96;
 pub const CD_HEAD_SIZE: i32 = 4;
 pub const CD_SUBHEAD_SIZE: i32 = 8;
@@ -185,9 +207,23 @@ pub const CD_ECC_SIZE: i32 = 276;
 pub const CD_FRAMESIZE: i32 = 2048;
 pub const CD_FRAMESIZE_RAW: i32 = 2352;
 pub const CD_FRAMESIZE_RAWER: i32 = 2646;
+/// Number of 16-bit stereo samples in a raw audio frame
+pub const CD_SAMPLES_PER_FRAME: i32 = CD_FRAMESIZE_RAW / 4;
 pub const CD_FRAMESIZE_RAW1: i32 = CD_FRAMESIZE_RAW - CD_SYNC_SIZE;
 pub const CD_FRAMESIZE_RAW0: i32 = CD_FRAMESIZE_RAW - CD_SYNC_SIZE - CD_HEAD_SIZE;
 
+/// Slot argument that refers to the current disc, rather than a changer slot
+pub const CDSL_CURRENT: i32 = i32::MAX;
+
+/// Flag set by [`Operation::TimedMediaChange`] when the media changed after the time passed in
+pub const MEDIA_CHANGED_FLAG: u64 = 0x1;
+
+/// Track number of the lead-out in the table of contents, which is not a real track
+pub const CDROM_LEADOUT: u8 = 0xAA;
+
+/// Data rate of a 1x drive reading audio, in KB/s
+pub const CD_SPEED_1X_KBPS: u16 = 176;
+
 pub const CD_XA_HEAD: i32 = CD_HEAD_SIZE + CD_SUBHEAD_SIZE;
 pub const CD_XA_TAIL: i32 = CD_EDC_SIZE + CD_ECC_SIZE;
 pub const CD_XA_SYNC_HEAD: i32 = CD_SYNC_SIZE + CD_XA_HEAD;
@@ -200,6 +236,142 @@ pub enum AddressType {
     Msf = 0x02,
 }
 
+/// Response formats of the READ TOC/PMA/ATIP packet command
+#[repr(u8)]
+#[derive(FromPrimitive, ToPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TocFormat {
+    /// Table of contents, with a descriptor for every track
+    Toc = 0x00,
+    /// First track of the last session
+    SessionInfo = 0x01,
+    /// Every Q subchannel entry in the lead-in, including session numbers
+    FullToc = 0x02,
+    /// Program Memory Area of a recordable disc
+    Pma = 0x03,
+    /// Absolute Time In Pregroove of a recordable disc
+    Atip = 0x04,
+    CdText = 0x05,
+}
+
+/// Directions the SCAN packet command can move in
+#[repr(u8)]
+#[derive(FromPrimitive, ToPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanDirection {
+    /// Fast forward
+    Forward = 0,
+    /// Rewind
+    Reverse = 1,
+}
+
+/// Expected sector types for the READ CD packet command
+#[repr(u8)]
+#[derive(FromPrimitive, ToPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectorType {
+    /// Any type of sector, returned raw
+    Any = 0x00,
+    /// Red Book audio
+    CdDa = 0x01,
+    /// Yellow Book Mode 1 data
+    Mode1 = 0x02,
+    /// Mode 2 data without Form 1 or Form 2 subheaders
+    Mode2 = 0x03,
+    /// Mode 2 Form 1 data
+    Mode2Form1 = 0x04,
+    /// Mode 2 Form 2 data
+    Mode2Form2 = 0x05,
+}
+
+impl SectorType {
+    /// Number of bytes returned for each sector of this type
+    pub fn sector_size(&self) -> usize {
+        match self {
+            SectorType::Any | SectorType::CdDa => CD_FRAMESIZE_RAW as usize,
+            SectorType::Mode1 | SectorType::Mode2Form1 => CD_FRAMESIZE as usize,
+            SectorType::Mode2 => CD_FRAMESIZE_RAW0 as usize,
+            SectorType::Mode2Form2 => 2324,
+        }
+    }
+}
+
+/// Size of the C2 error pointers for a single sector, one bit per byte
+pub const CD_C2_SIZE: i32 = CD_FRAMESIZE_RAW / 8;
+
+/// Recording state of a disc, returned by the READ DISC INFORMATION packet command
+#[derive(FromPrimitive, ToPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscState {
+    Empty = 0,
+    Incomplete = 1,
+    Finalized = 2,
+    /// Media that can't be written to in sessions, such as a pressed disc
+    Other = 3,
+}
+
+/// Recording state of the last session on a disc
+#[derive(FromPrimitive, ToPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionState {
+    Empty = 0,
+    Incomplete = 1,
+    Damaged = 2,
+    Complete = 3,
+}
+
+/// State of the drive mechanism reported by the MECHANISM STATUS packet command
+#[derive(FromPrimitive, ToPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MechanismState {
+    Idle = 0,
+    PlayingAudio = 1,
+    Scanning = 2,
+    /// Busy with a command from the host
+    Active = 3,
+    /// The drive doesn't report its state
+    NoInfo = 7,
+}
+
+/// State of a CD changer reported by the MECHANISM STATUS packet command
+#[derive(FromPrimitive, ToPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangerState {
+    Ready = 0,
+    Loading = 1,
+    Unloading = 2,
+    Initializing = 3,
+}
+
+/// Mode page of the CD/DVD capabilities and mechanical status, such as the maximum speed
+pub const MODE_PAGE_CAPABILITIES: u8 = 0x2A;
+
+/// Feature code of the Profile List, which lists the profiles a drive supports
+pub const FEATURE_PROFILE_LIST: u16 = 0x0000;
+
+/// Media profiles reported by the GET CONFIGURATION packet command
+#[repr(u16)]
+#[derive(FromPrimitive, ToPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    NoProfile = 0x0000,
+    NonRemovable = 0x0001,
+    Removable = 0x0002,
+    CdRom = 0x0008,
+    CdR = 0x0009,
+    CdRw = 0x000A,
+    DvdRom = 0x0010,
+    DvdR = 0x0011,
+    DvdRam = 0x0012,
+    DvdRwOverwrite = 0x0013,
+    DvdRwSequential = 0x0014,
+    DvdPlusRw = 0x001A,
+    DvdPlusR = 0x001B,
+    BdRom = 0x0040,
+    BdR = 0x0041,
+    BdRe = 0x0043,
+}
+
 #[derive(FromPrimitive, ToPrimitive)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AudioStates {
@@ -211,6 +383,8 @@ pub enum AudioStates {
     NoStatus = 0x15,
 }
 
+/// Capability flags returned by the CDROM_GET_CAPABILITY ioctl
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Capability {
     CloseTray = 0x01,
     OpenTray = 0x02,
@@ -222,6 +396,7 @@ pub enum Capability {
     MediaChanged = 0x80,
     PlayAudio = 0x100,
     Reset = 0x200,
+    Ioctls = 0x400,
     DriveStatus = 0x800,
     GenericPacket = 0x1000,
     CdR = 0x2000,
@@ -231,10 +406,6 @@ pub enum Capability {
     DvdRam = 0x20000,
     MODrive = 0x40000,
     Mrw = 0x80000,
-    MrwW = 0x1000000,
-    Ram = 0x2000000,
-}
-
-pub enum GenericPacketCommand {
-
+    MrwW = 0x100000,
+    Ram = 0x200000,
 }
@@ -0,0 +1,78 @@
+//! Generation of CUE sheets for single file rips.
+
+use crate::{constants, structures::Track};
+
+/// Generate a CUE sheet describing `tracks`, all stored in the file `wav_filename`.
+///
+/// The file is assumed to start at LBA 0. Pregaps are estimated from the table of
+/// contents with [`estimate_pregaps`]; use [`generate_with_pregaps`] with the result
+/// of [`CDRom::pregaps`](crate::CDRom::pregaps) for exact ones.
+pub fn generate(wav_filename: &str, tracks: &[Track]) -> String {
+    generate_with_pregaps(wav_filename, tracks, &estimate_pregaps(tracks))
+}
+
+/// Generate a CUE sheet describing `tracks`, all stored in the file `wav_filename`,
+/// with the given pregap length in frames of each track.
+///
+/// Tracks with a pregap get an `INDEX 00` line marking where the gap starts.
+pub fn generate_with_pregaps(wav_filename: &str, tracks: &[Track], pregaps: &[(u8, u32)]) -> String {
+    let mut cue = format!("FILE \"{}\" WAVE\n", wav_filename);
+
+    for track in tracks {
+        let start = track.start.into_lba();
+        let pregap = pregaps
+            .iter()
+            .find(|(number, _)| *number == track.number)
+            .map_or(0, |(_, frames)| *frames as i32);
+
+        let track_type = if track.is_audio { "AUDIO" } else { "MODE1/2352" };
+        cue.push_str(&format!("  TRACK {:02} {}\n", track.number, track_type));
+
+        if let Some(isrc) = &track.isrc {
+            cue.push_str(&format!("    ISRC {}\n", isrc));
+        }
+
+        if pregap > 0 {
+            cue.push_str(&format!("    INDEX 00 {}\n", cue_time(start - pregap)));
+        }
+        cue.push_str(&format!("    INDEX 01 {}\n", cue_time(start)));
+    }
+
+    cue
+}
+
+/// Estimate the pregap length in frames of each track from the table of contents alone.
+///
+/// The first track has a pregap if it doesn't start at LBA 0, and any track whose type
+/// differs from the previous one has the 2 second gap that requires. Every other track
+/// is assumed to have no pregap.
+pub fn estimate_pregaps(tracks: &[Track]) -> Vec<(u8, u32)> {
+    let mut previous: Option<&Track> = None;
+
+    tracks
+        .iter()
+        .map(|track| {
+            let pregap = match previous {
+                None => track.start.into_lba().max(0),
+                Some(p) if p.is_audio != track.is_audio => constants::CD_MSF_OFFSET,
+                Some(_) => 0,
+            };
+            previous = Some(track);
+
+            (track.number, pregap as u32)
+        })
+        .collect()
+}
+
+/// Format a frame offset from the start of the file as `mm:ss:ff`.
+fn cue_time(frames: i32) -> String {
+    let frames = frames.max(0);
+    let seconds = frames / constants::CD_FRAMES;
+
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / constants::CD_SECS,
+        seconds % constants::CD_SECS,
+        frames % constants::CD_FRAMES,
+    )
+}
@@ -0,0 +1,108 @@
+//! A snapshot of the table of contents and metadata of a disc, read once so it can be
+//! used without going back to the drive.
+
+use crate::constants::{self, AddressType};
+use crate::structures::{Mcn, Msf, Track};
+use crate::{musicbrainz, CDRom, CDRomError};
+
+/// The tracks and metadata of a disc, as read by [`CDRom::read_disc`].
+///
+/// Nothing here issues an ioctl except the methods that take the drive, so a `Disc` can
+/// be kept around while the drive is used for something else. Check
+/// [`Disc::is_current`] before relying on it after the disc may have been swapped.
+#[derive(Debug, Clone)]
+pub struct Disc {
+    first_track: u8,
+    last_track: u8,
+    tracks: Vec<Track>,
+    /// LBA of the lead-out
+    leadout: i32,
+    mcn: Option<Mcn>,
+}
+
+impl Disc {
+    /// Every track on the disc, with their ISRCs where the drive could read them.
+    pub fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    /// The track with the number `number`, if the disc has it.
+    pub fn track(&self, number: u8) -> Option<&Track> {
+        self.tracks.iter().find(|t| t.number == number)
+    }
+
+    /// The Media Catalog Number of the disc, if it has a valid one.
+    pub fn mcn(&self) -> Option<&Mcn> {
+        self.mcn.as_ref()
+    }
+
+    /// The playing time of the disc, from LBA 0 up to the lead-out.
+    pub fn length(&self) -> Msf {
+        Msf::from_lba(self.leadout.max(0) - constants::CD_MSF_OFFSET)
+    }
+
+    /// The number of sectors on the disc, from LBA 0 up to the lead-out.
+    pub fn total_sectors(&self) -> u32 {
+        self.leadout.max(0) as u32
+    }
+
+    /// The MusicBrainz Disc ID of the disc.
+    pub fn musicbrainz_id(&self) -> String {
+        let offsets: Vec<i32> = self.tracks.iter().map(|t| t.start.into_lba()).collect();
+
+        musicbrainz::disc_id(self.first_track, self.last_track, self.leadout, &offsets)
+    }
+
+    /// Check whether `drive` still holds this disc, by asking whether the media has
+    /// changed since it was read.
+    ///
+    /// This consumes the drive's media changed flag, like [`CDRom::media_changed`].
+    pub fn is_current(&self, drive: &mut CDRom) -> Result<bool, CDRomError> {
+        Ok(!drive.media_changed()?)
+    }
+
+    /// Read all the audio in the track with the number `number` from `drive`.
+    ///
+    /// Returns [`CDRomError::InvalidAddress`] if the disc has no such track, and
+    /// [`CDRomError::NotAudioCD`] if it is a data track.
+    pub fn read_track(&self, drive: &mut CDRom, number: u8) -> Result<Vec<i16>, CDRomError> {
+        let track = self.track(number).ok_or(CDRomError::InvalidAddress)?;
+        if !track.is_audio {
+            return Err(CDRomError::NotAudioCD)
+        }
+
+        let frames = track.length_frames as usize;
+        let mut samples = vec![0i16; frames * constants::CD_FRAMESIZE_RAW as usize / 2];
+        drive.read_audio_range(track.start, frames, &mut samples)?;
+
+        Ok(samples)
+    }
+}
+
+impl CDRom {
+    /// Read the table of contents, MCN, and ISRCs of the disc once, so they can be
+    /// used without issuing any more ioctls.
+    ///
+    /// This also clears the media changed flag, so [`Disc::is_current`] only reports
+    /// changes that happen after this call.
+    pub fn read_disc(&mut self) -> Result<Disc, CDRomError> {
+        // A drive that can't report media changes can still be read
+        match self.media_changed() {
+            Ok(_) | Err(CDRomError::Unsupported) => (),
+            Err(e) => return Err(e),
+        }
+
+        let header = self.toc_header()?;
+        let leadout = self.leadout(AddressType::Lba)?.addr.into_lba();
+        let tracks = self.tracks()?;
+        let mcn = self.media_catalog().ok().flatten();
+
+        Ok(Disc {
+            first_track: header.first_track,
+            last_track: header.last_track,
+            tracks,
+            leadout,
+            mcn,
+        })
+    }
+}
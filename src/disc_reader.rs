@@ -0,0 +1,88 @@
+//! Byte-level access to the data on a CD, for use with anything that takes an
+//! [`std::io::Read`] and [`std::io::Seek`].
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::{constants, structures::Addr, CDRom};
+
+const SECTOR_SIZE: usize = constants::CD_FRAMESIZE as usize;
+
+/// Reads the user data of a range of Mode 1 sectors as a continuous stream of bytes.
+pub struct DiscReader<'a> {
+    drive: &'a mut CDRom,
+    /// First sector of the range
+    start_lba: i32,
+    /// Sector after the last one in the range
+    end_lba: i32,
+    /// Current position in bytes from the start of the range
+    position: u64,
+    /// Sector currently held in `buffer`
+    buffered: Option<i32>,
+    buffer: Vec<u8>,
+}
+
+impl<'a> DiscReader<'a> {
+    /// Create a reader over the sectors from `start_lba` up to, but not including, `end_lba`.
+    pub fn new(drive: &'a mut CDRom, start_lba: i32, end_lba: i32) -> Self {
+        Self {
+            drive,
+            start_lba,
+            end_lba: end_lba.max(start_lba),
+            position: 0,
+            buffered: None,
+            buffer: vec![0u8; SECTOR_SIZE],
+        }
+    }
+
+    /// Total length of the range in bytes.
+    pub fn len(&self) -> u64 {
+        (self.end_lba - self.start_lba) as u64 * SECTOR_SIZE as u64
+    }
+
+    /// Whether the range contains no sectors.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Read for DiscReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.len() {
+            return Ok(0)
+        }
+
+        let sector = self.start_lba + (self.position / SECTOR_SIZE as u64) as i32;
+        let offset = (self.position % SECTOR_SIZE as u64) as usize;
+
+        if self.buffered != Some(sector) {
+            self.buffered = None;
+            self.drive
+                .read_mode1(Addr::Lba(sector), &mut self.buffer)
+                .map_err(io::Error::other)?;
+            self.buffered = Some(sector);
+        }
+
+        let n = buf.len().min(SECTOR_SIZE - offset);
+        buf[..n].copy_from_slice(&self.buffer[offset..offset + n]);
+        self.position += n as u64;
+
+        Ok(n)
+    }
+}
+
+impl Seek for DiscReader<'_> {
+    /// Move to a byte offset within the range. Offsets outside the range are
+    /// clamped to its start or end.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => n.min(i64::MAX as u64) as i64,
+            SeekFrom::End(n) => (self.len() as i64).saturating_add(n),
+            SeekFrom::Current(n) => (self.position as i64).saturating_add(n),
+        };
+
+        self.position = target.clamp(0, self.len() as i64) as u64;
+        self.buffered = None;
+
+        Ok(self.position)
+    }
+}
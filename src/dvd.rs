@@ -0,0 +1,78 @@
+//! DVD specific commands, which are kept apart from the CD-centric API of [`CDRom`].
+
+use num_traits::FromPrimitive as _;
+
+use crate::packet_commands::{DataDirection, GenericPacketCommand, CDROM_PACKET_SIZE};
+use crate::{CDRom, CDRomError};
+
+/// Size of the physical format information returned by READ DVD STRUCTURE
+const PHYSICAL_FORMAT_SIZE: usize = 2048;
+
+/// Book type of a DVD, as recorded in its physical format information
+#[derive(FromPrimitive, ToPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DvdBookType {
+    DvdRom = 0x0,
+    DvdRam = 0x1,
+    DvdR = 0x2,
+    DvdRw = 0x3,
+    DvdPlusRw = 0x9,
+    DvdPlusR = 0xA,
+}
+
+/// Physical layout of a DVD, from the physical format information in its lead-in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DvdPhysical {
+    /// `None` if the book type is not one of the known types
+    pub book_type: Option<DvdBookType>,
+    pub layers: u8,
+    /// The second layer is read from the outside in, rather than as a separate area
+    pub opposite_track_path: bool,
+    /// First physical sector of the data area
+    pub start_sector: u32,
+    /// Last physical sector of the data area
+    pub end_sector: u32,
+    /// Last physical sector of the first layer, which is only set for discs with
+    /// more than one layer
+    pub layer0_end_sector: u32,
+}
+
+impl DvdPhysical {
+    /// Number of sectors in the first layer, which is where the layer break falls.
+    ///
+    /// Returns `None` for single layer discs.
+    pub fn layer_break(&self) -> Option<u32> {
+        if self.layers < 2 || self.layer0_end_sector < self.start_sector {
+            return None
+        }
+
+        Some(self.layer0_end_sector - self.start_sector + 1)
+    }
+}
+
+impl CDRom {
+    /// Read the physical format information of a DVD, such as the number of layers and
+    /// where the layer break is.
+    pub fn dvd_physical_info(&mut self) -> Result<DvdPhysical, CDRomError> {
+        let mut buffer = vec![0u8; 4 + PHYSICAL_FORMAT_SIZE];
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::ReadDvdStructure as u8;
+        // Format 0 of layer 0: physical format information
+        cdb[8..10].copy_from_slice(&(buffer.len() as u16).to_be_bytes());
+
+        self.packet_command(cdb, &mut buffer, DataDirection::Read)?;
+
+        let info = &buffer[4..];
+        let sector = |i: usize| u32::from_be_bytes([0, info[i + 1], info[i + 2], info[i + 3]]);
+
+        Ok(DvdPhysical {
+            book_type: DvdBookType::from_u8(info[0] >> 4),
+            layers: ((info[2] >> 5) & 0x03) + 1,
+            opposite_track_path: info[2] & 0x10 != 0,
+            start_sector: sector(4),
+            end_sector: sector(8),
+            layer0_end_sector: sector(12),
+        })
+    }
+}
@@ -0,0 +1,158 @@
+//! An in-memory CD for testing code that uses [`CDRom`] without a drive.
+//!
+//! There is no trait over [`CDRom`] to implement a fake drive with. Instead the fake
+//! swaps out the [`IoctlBackend`](crate::backend::IoctlBackend) that every ioctl goes
+//! through, so the code under test keeps using the real [`CDRom`].
+
+use crate::backend::MockBackend;
+use crate::constants::{self, DiscType, Status};
+use crate::CDRom;
+
+const FRAME_VALUES: usize = constants::CD_FRAMESIZE_RAW as usize / 2;
+
+/// Builds a [`CDRom`] backed by a synthetic disc.
+///
+/// Tracks are laid out one after another from LBA 0, in the order they are added.
+/// The table of contents, subchannel, and audio reads all agree with each other.
+#[derive(Debug, Clone, Default)]
+pub struct FakeCDRom {
+    backend: MockBackend,
+}
+
+impl FakeCDRom {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an audio track holding the interleaved stereo `samples`, padded with
+    /// silence to a whole number of frames.
+    pub fn audio_track(mut self, samples: &[i16]) -> Self {
+        let start = self.end();
+        self.backend.tracks.push((start, 0x00));
+        self.backend.audio.extend_from_slice(samples);
+
+        let padded = self.backend.audio.len().div_ceil(FRAME_VALUES) * FRAME_VALUES;
+        self.backend.audio.resize(padded, 0);
+        self.backend.leadout = self.end();
+        self
+    }
+
+    /// Add a data track that is `frames` long.
+    pub fn data_track(mut self, frames: u32) -> Self {
+        let start = self.end();
+        self.backend.tracks.push((start, 0x04));
+
+        let len = self.backend.audio.len() + frames as usize * FRAME_VALUES;
+        self.backend.audio.resize(len, 0);
+        self.backend.leadout = self.end();
+        self
+    }
+
+    /// Make reads that include the sector at `lba` fail, as if the disc were damaged there.
+    pub fn read_error_at(mut self, lba: i32) -> Self {
+        self.backend.bad_sectors.push(lba);
+        self
+    }
+
+    /// Report `lba` as the current position in subchannel reads, as if the drive had
+    /// just read it.
+    pub fn position(mut self, lba: i32) -> Self {
+        self.backend.position = lba;
+        self
+    }
+
+    /// Leave the drive without a disc.
+    pub fn no_disc(mut self) -> Self {
+        self.backend.status = Status::NoDisc;
+        self
+    }
+
+    pub fn build(mut self) -> CDRom {
+        let data_tracks = self.backend.tracks.iter().filter(|t| t.1 & 0x04 != 0).count();
+        self.backend.disc_type = match data_tracks {
+            0 => DiscType::Audio,
+            n if n == self.backend.tracks.len() => DiscType::Data1,
+            _ => DiscType::Mixed,
+        };
+
+        CDRom::with_backend(self.backend)
+    }
+
+    /// LBA just past the end of the last track.
+    fn end(&self) -> i32 {
+        (self.backend.audio.len() / FRAME_VALUES) as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::AddressType;
+    use crate::structures::Addr;
+    use crate::CDRomError;
+
+    #[test]
+    fn mixed_disc_agrees_everywhere() {
+        // 1.5 frames of audio, padded up to 2
+        let samples: Vec<i16> = (0..FRAME_VALUES as i16 * 3 / 2).collect();
+        let mut drive = FakeCDRom::new()
+            .audio_track(&samples)
+            .data_track(10)
+            .audio_track(&[1; FRAME_VALUES * 3])
+            .position(14)
+            .build();
+
+        assert_eq!(drive.disc_type().unwrap(), Some(DiscType::Mixed));
+
+        let tracks = drive.tracks().unwrap();
+        assert_eq!(tracks.iter().map(|t| t.start.into_lba()).collect::<Vec<_>>(), [0, 2, 12]);
+        assert_eq!(tracks.iter().map(|t| t.length_frames).collect::<Vec<_>>(), [2, 10, 3]);
+        assert_eq!(tracks.iter().map(|t| t.is_audio).collect::<Vec<_>>(), [true, false, true]);
+
+        for track in &tracks {
+            let entry = drive.toc_entry(track.number, AddressType::Lba).unwrap();
+            assert_eq!(entry.addr.into_lba(), track.start.into_lba());
+            assert_eq!(entry.is_audio(), track.is_audio);
+
+            let entry = drive.toc_entry(track.number, AddressType::Msf).unwrap();
+            assert_eq!(entry.addr.into_lba(), track.start.into_lba());
+        }
+        let leadout = drive.toc_entry(constants::CDROM_LEADOUT, AddressType::Lba).unwrap();
+        assert_eq!(leadout.addr.into_lba(), 15);
+
+        let sub = drive.subchannel(AddressType::Lba).unwrap();
+        assert_eq!(sub.trk, 3);
+        assert_eq!(sub.absaddr.into_lba(), 14);
+        assert_eq!(sub.reladdr.into_lba(), 2);
+
+        let audio = drive.read_audio(Addr::Lba(0), 2).unwrap();
+        assert_eq!(audio[..samples.len()], samples[..]);
+        assert!(audio[samples.len()..].iter().all(|s| *s == 0));
+        assert!(drive.read_audio(Addr::Lba(12), 3).unwrap().iter().all(|s| *s == 1));
+    }
+
+    #[test]
+    fn read_error_at_fails_reads_of_that_sector() {
+        let mut drive = FakeCDRom::new()
+            .audio_track(&[0; FRAME_VALUES * 20])
+            .read_error_at(7)
+            .build();
+
+        assert!(drive.read_audio(Addr::Lba(0), 7).is_ok());
+        assert!(matches!(
+            drive.read_audio(Addr::Lba(7), 1),
+            Err(CDRomError::ReadError { address: Some(Addr::Lba(7)) })
+        ));
+    }
+
+    #[test]
+    fn no_disc_is_no_disc() {
+        let mut drive = FakeCDRom::new()
+            .audio_track(&[0; FRAME_VALUES])
+            .no_disc()
+            .build();
+
+        assert!(matches!(drive.tracks(), Err(CDRomError::NoDisc)));
+        assert!(matches!(drive.read_audio(Addr::Lba(0), 1), Err(CDRomError::NoDisc)));
+    }
+}
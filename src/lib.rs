@@ -0,0 +1,2403 @@
+pub mod accuraterip;
+#[cfg(feature = "tokio")]
+pub mod async_cdrom;
+pub mod backend;
+pub mod cd_text;
+pub mod constants;
+pub mod cue;
+pub mod disc;
+pub mod disc_reader;
+pub mod dvd;
+#[cfg(feature = "test-util")]
+pub mod fake;
+pub mod musicbrainz;
+pub mod packet_commands;
+pub mod paranoia;
+pub mod sector;
+pub mod structures;
+#[cfg(feature = "wav")]
+pub mod wav;
+
+use std::io::{self, Write};
+use std::os::fd::{FromRawFd, OwnedFd, RawFd};
+use std::path::PathBuf;
+use std::os::unix::fs::OpenOptionsExt;
+use std::fs::OpenOptions;
+use std::ptr::addr_of_mut;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use backend::{IoctlBackend, SystemBackend};
+use cd_text::CdText;
+use constants::{op_to_ioctl, AddressType, ChangerState, DiscState, DiscType, MechanismState, Operation, ScanDirection, SectorType, SessionState, Status, TocFormat};
+use nix::errno::Errno;
+use nix::libc;
+
+use num_traits::FromPrimitive as _;
+use packet_commands::{DataDirection, GenericCommand, GenericPacketCommand, RequestSense, CDROM_PACKET_SIZE, MODE_HEADER_SIZE};
+use structures::{Addr, AddrUnion, Atip, Blk, QSubChannel, Capabilities, CdOptions, DiscInformation, DriveEvent, DriveInfo, Feature, MechanismStatus, MediaEvent, Mcn, Msf, MsfLong, MultiSession, PerfDescriptor, ReadAudio, SubChannel, TocEntry, TocHeader, Track, TrackIndex, TrackInformation, VerifyStats, Volume, _MultiSession, _TimedMediaChangeInfo, _SubChannel, _TocEntry};
+use thiserror::Error;
+
+#[macro_use]
+extern crate num_derive;
+
+/// Access to a CD-ROM drive on the system.
+///
+/// A `CDRom` is [`Send`], so it can be opened on one thread and handed to another, but
+/// not [`Sync`]. Every method takes `&mut self`, since issuing ioctls on the same drive
+/// from several threads at once is not safe.
+pub struct CDRom {
+    backend: Box<dyn IoctlBackend>,
+    /// Time of the last media change reported by [`CDRom::timed_media_change`]
+    last_media_change: i64,
+}
+
+// Keep CDRom movable between threads, which every backend must allow
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    assert_send::<CDRom>();
+};
+
+/// Options for opening a CD-ROM drive, created with [`CDRom::builder`].
+///
+/// By default `/dev/sr0` is opened with `O_NONBLOCK`, like [`CDRom::new`] does.
+#[derive(Debug, Clone)]
+pub struct CDRomBuilder {
+    path: PathBuf,
+    blocking: bool,
+}
+
+impl CDRomBuilder {
+    /// Set the path of the device to open.
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Set whether the device is opened without `O_NONBLOCK`.
+    ///
+    /// Opening the drive without `O_NONBLOCK` changes how the kernel treats it:
+    /// - Opening fails with [`CDRomError::NoDisc`] when there is no disc, and the
+    ///   tray is closed first if [`CdOptions::AUTO_CLOSE`] is set.
+    /// - If [`CdOptions::CHECK_TYPE`] is set, opening a disc with no data tracks fails,
+    ///   so audio CDs can't be opened this way.
+    /// - If [`CdOptions::LOCK`] is set, the door is locked for as long as the drive is open.
+    ///
+    /// Once open, ioctls behave the same either way, and reads always wait for the
+    /// drive to return data.
+    pub fn blocking(mut self, blocking: bool) -> Self {
+        self.blocking = blocking;
+        self
+    }
+
+    /// Open the drive with the chosen options.
+    pub fn open(self) -> Result<CDRom, CDRomError> {
+        let flags = if self.blocking { libc::O_RDONLY } else { libc::O_NONBLOCK | libc::O_RDONLY };
+
+        let drive_file = match OpenOptions::new().read(true).custom_flags(flags).open(&self.path) {
+            Ok(f) => f,
+            Err(e) if e.raw_os_error() == Some(libc::ENOMEDIUM) => return Err(CDRomError::NoDisc),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(CDRom::with_backend(SystemBackend::new(drive_file.into())))
+    }
+}
+
+impl Default for CDRomBuilder {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("/dev/sr0"),
+            blocking: false,
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum CDRomError {
+    #[error("internal system error")]
+    Errno(#[from] nix::errno::Errno),
+
+    #[error("no disc in drive to read")]
+    NoDisc,
+
+    #[error("the CD does not contain cd-audio")]
+    NotAudioCD,
+
+    #[error("the disc is not recordable media")]
+    NotRecordable,
+
+    #[error("the drive's door is locked for some reason")]
+    DoorLocked,
+
+    #[error("this drive does not support the function")]
+    Unsupported,
+
+    #[error("the drive is in use by another user")]
+    Busy,
+
+    /// `address` is the sector that failed, when it is known
+    #[error("the drive was unable to read the requested sector")]
+    ReadError { address: Option<Addr> },
+
+    #[error("the address specified was invalid")]
+    InvalidAddress,
+
+    #[error("the drive rejected the command; sense key {key:#04x}, ASC {asc:#04x}, ASCQ {ascq:#04x}")]
+    Sense { key: u8, asc: u8, ascq: u8 },
+
+    #[error("the drive returned a response that doesn't follow the protocol")]
+    ProtocolViolation,
+
+    #[error("failed to write the output: {0}")]
+    Io(io::ErrorKind),
+
+    #[error("timed out waiting for the drive")]
+    Timeout,
+
+    #[error("the buffer size was too small; needed at least {0} bytes, got {1} bytes")]
+    InvalidBufferSize(usize, usize),
+}
+
+impl From<io::Error> for CDRomError {
+    fn from(e: io::Error) -> Self {
+        CDRomError::Io(e.kind())
+    }
+}
+
+impl CDRomError {
+    /// Fill in the address of a [`CDRomError::ReadError`] that doesn't have one.
+    fn at(self, address: Addr) -> Self {
+        match self {
+            CDRomError::ReadError { address: None } => CDRomError::ReadError { address: Some(address) },
+            e => e,
+        }
+    }
+}
+
+/// Convert an error returned by an ioctl into the matching [`CDRomError`].
+///
+/// Methods handle errors specific to their ioctl first, and use this for the rest.
+fn errno_to_error(e: Errno) -> CDRomError {
+    match e {
+        Errno::ENOMEDIUM => CDRomError::NoDisc,
+        Errno::EBUSY => CDRomError::Busy,
+        Errno::ENOSYS | Errno::EOPNOTSUPP => CDRomError::Unsupported,
+        Errno::EIO => CDRomError::ReadError { address: None },
+        _ => CDRomError::Errno(e),
+    }
+}
+
+/// Except field of GET PERFORMANCE asking for the nominal performance
+const PERFORMANCE_NOMINAL: u8 = 0x00;
+/// Except field of GET PERFORMANCE asking for the whole list of exceptions
+const PERFORMANCE_EXCEPTIONS: u8 = 0x01;
+
+/// Byte 9 of a READ CD or READ CD MSF command, which selects the fields of each
+/// sector to return.
+fn read_cd_fields(sector_type: SectorType, with_c2: bool) -> u8 {
+    let fields = match sector_type {
+        // Sync, all headers, user data, and EDC/ECC
+        SectorType::Any => 0xF8,
+        // User data only
+        _ => 0x10,
+    };
+
+    if with_c2 { fields | 0x02 } else { fields }
+}
+
+impl CDRom {
+    /// Creates a new interface to a system CD-ROM drive.
+    pub fn new() -> Option<Self> {
+        Self::builder().open().ok()
+    }
+
+    /// Choose how to open a drive, such as which device to open and whether to
+    /// use `O_NONBLOCK`.
+    pub fn builder() -> CDRomBuilder {
+        CDRomBuilder::default()
+    }
+
+    /// Creates an interface to a drive that is already open, taking ownership of `fd`.
+    ///
+    /// The descriptor is closed when the `CDRom` is dropped.
+    ///
+    /// # Safety
+    /// `fd` must be an open descriptor of a CD-ROM device that nothing else owns. It
+    /// should be opened for reading with `O_NONBLOCK`, like [`CDRom::new`] does, or
+    /// opening it will have failed when there is no disc.
+    pub unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self::with_backend(SystemBackend::new(OwnedFd::from_raw_fd(fd)))
+    }
+
+    /// Creates an interface that issues every ioctl through `backend`, such as a
+    /// [`MockBackend`](backend::MockBackend) for testing without a drive.
+    pub fn with_backend(backend: impl IoctlBackend + 'static) -> Self {
+        Self {
+            backend: Box::new(backend),
+            last_media_change: 0,
+        }
+    }
+
+    /// Get the currently reported status of the drive.
+    pub fn status(&mut self) -> Result<Status, CDRomError> {
+        let status = match unsafe {
+            self.ioctl_none(Operation::DriveStatus)
+        } {
+            Ok(v) => v,
+            Err(e) => return Err(errno_to_error(e)),
+        };
+
+        Status::from_i32(status).ok_or(CDRomError::ProtocolViolation)
+    }
+
+    /// Wait until the drive reports a disc is ready, checking once a second.
+    ///
+    /// See [`CDRom::wait_for_disc_polling`] for details.
+    pub fn wait_for_disc(&mut self, timeout: Duration) -> Result<Status, CDRomError> {
+        self.wait_for_disc_polling(timeout, Duration::from_secs(1))
+    }
+
+    /// Wait until the drive reports a disc is ready, checking every `poll_interval`.
+    ///
+    /// If the tray is open it is closed, or waited on if the drive can't close it.
+    /// Returns [`Status::DiscOK`] once the disc is ready, or [`CDRomError::Timeout`]
+    /// if that doesn't happen within `timeout`.
+    pub fn wait_for_disc_polling(&mut self, timeout: Duration, poll_interval: Duration) -> Result<Status, CDRomError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            match self.status()? {
+                Status::DiscOK => return Ok(Status::DiscOK),
+                // Drives that can't close their own tray wait for the user to close it
+                Status::TrayOpen => match self.close() {
+                    Ok(_) | Err(CDRomError::Unsupported) => (),
+                    Err(e) => return Err(e),
+                },
+                _ => (),
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(CDRomError::Timeout)
+            }
+
+            thread::sleep(poll_interval.min(deadline - now));
+        }
+    }
+
+    /// Check whether the disc has been changed since the last time this was called.
+    ///
+    /// The first call after opening the drive may report `true` even if the disc
+    /// hasn't changed, so callers should treat it as a cue to read the disc.
+    pub fn media_changed(&mut self) -> Result<bool, CDRomError> {
+        match unsafe {
+            self.ioctl_int(Operation::MediaChanged, constants::CDSL_CURRENT)
+        } {
+            Ok(changed) => Ok(changed != 0),
+            Err(e) => Err(errno_to_error(e)),
+        }
+    }
+
+    /// Get the time of the last media change, if there has been one since the last time
+    /// this was called.
+    ///
+    /// Unlike [`CDRom::media_changed`], the time lets events from several drives be put
+    /// in order. The first call reports the last media change since boot, if any.
+    pub fn timed_media_change(&mut self) -> Result<Option<SystemTime>, CDRomError> {
+        let mut info = _TimedMediaChangeInfo {
+            last_media_change: self.last_media_change,
+            ..Default::default()
+        };
+
+        if let Err(e) = unsafe {
+            self.ioctl(Operation::TimedMediaChange, addr_of_mut!(info))
+        } {
+            return match e {
+                Errno::ENOTTY | Errno::EINVAL => Err(CDRomError::Unsupported),
+                _ => Err(errno_to_error(e)),
+            }
+        }
+
+        if info.media_flags & constants::MEDIA_CHANGED_FLAG == 0 {
+            return Ok(None)
+        }
+        self.last_media_change = info.last_media_change;
+
+        // The kernel reports the time on the monotonic clock, so convert it by how
+        // long ago it was
+        let mut now = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+        unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) };
+        let now_ms = now.tv_sec * 1000 + now.tv_nsec / 1_000_000;
+        let ago = Duration::from_millis(now_ms.saturating_sub(info.last_media_change).max(0) as u64);
+
+        Ok(Some(SystemTime::now() - ago))
+    }
+
+    /// Get the number of disc slots in a CD changer.
+    ///
+    /// Returns [`CDRomError::Unsupported`] for single disc drives.
+    pub fn changer_slots(&mut self) -> Result<u8, CDRomError> {
+        let slots = match unsafe {
+            self.ioctl_none(Operation::ChangerNslots)
+        } {
+            Ok(v) => v,
+            Err(e) => return Err(errno_to_error(e)),
+        };
+
+        match slots {
+            s if s <= 1 => Err(CDRomError::Unsupported),
+            s => Ok(s.min(u8::MAX as i32) as u8),
+        }
+    }
+
+    /// Load the disc in the given slot of a CD changer, starting from `0`.
+    ///
+    /// Returns [`CDRomError::Unsupported`] for single disc drives.
+    pub fn select_disc(&mut self, slot: u8) -> Result<(), CDRomError> {
+        match unsafe {
+            self.ioctl_int(Operation::SelectDisk, slot as i32)
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => match e {
+                Errno::EINVAL => Err(CDRomError::InvalidAddress),
+                _ => Err(errno_to_error(e)),
+            },
+        }
+    }
+
+    /// Get the drive's spindown timeout.
+    ///
+    /// The value is the inactivity timer from the CD parameters mode page, where
+    /// `0` is vendor specific, and `1` through `15` select 125ms, 250ms, 500ms, 1s,
+    /// 2s, 4s, 8s, 16s, 32s, 1min, 2min, 4min, 8min, 16min, and 32min.
+    ///
+    /// Only a few drivers implement this; others return [`CDRomError::Unsupported`].
+    pub fn spindown(&mut self) -> Result<u8, CDRomError> {
+        let mut value = 0u8;
+
+        match unsafe {
+            self.ioctl(Operation::GetSpindown, addr_of_mut!(value))
+        } {
+            Ok(_) => Ok(value),
+            Err(e) => match e {
+                Errno::ENOSYS | Errno::EOPNOTSUPP | Errno::ENOTTY | Errno::EINVAL => Err(CDRomError::Unsupported),
+                _ => Err(errno_to_error(e)),
+            },
+        }
+    }
+
+    /// Set the drive's spindown timeout, encoded as described in [`CDRom::spindown`].
+    ///
+    /// A high value keeps the disc spinning between tracks during long rips.
+    pub fn set_spindown(&mut self, value: u8) -> Result<(), CDRomError> {
+        match unsafe {
+            self.ioctl(Operation::SetSpindown, &value)
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => match e {
+                Errno::ENOSYS | Errno::EOPNOTSUPP | Errno::ENOTTY | Errno::EINVAL => Err(CDRomError::Unsupported),
+                _ => Err(errno_to_error(e)),
+            },
+        }
+    }
+
+    /// Get the capabilities of the drive.
+    pub fn capabilities(&mut self) -> Result<Capabilities, CDRomError> {
+        let capabilities = match unsafe {
+            self.ioctl_none(Operation::GetCapability)
+        } {
+            Ok(v) => v,
+            Err(e) => return Err(errno_to_error(e)),
+        };
+
+        Ok(Capabilities(capabilities as u32))
+    }
+
+    /// Get the type of disc currently in the drive.
+    ///
+    /// Returns `None` if the driver reports a status that isn't a disc type, like an
+    /// open tray.
+    pub fn disc_type(&mut self) -> Result<Option<DiscType>, CDRomError> {
+        let status = match unsafe {
+            self.ioctl_none(Operation::DiscStatus)
+        } {
+            Ok(v) => v,
+            Err(e) => return Err(errno_to_error(e)),
+        };
+
+        Ok(DiscType::from_i32(status))
+    }
+
+    /// Get the Media Catalog Number of the current disc.
+    ///
+    /// Many discs do not contain this information, in which case `None` is returned.
+    /// A number that isn't a valid 13 digit UPC/EAN is also treated as missing.
+    pub fn media_catalog(&mut self) -> Result<Option<Mcn>, CDRomError> {
+        let mut buffer = [0u8; 14];
+
+        if let Err(e) = unsafe {
+            self.ioctl(Operation::GetMcn, addr_of_mut!(buffer))
+        } {
+            return Err(errno_to_error(e))
+        }
+
+        let code = String::from_utf8_lossy(&buffer[..buffer.len() - 1]).into_owned();
+        if code.trim_matches(|c| c == '0' || c == ' ' || c == '\0').is_empty() {
+            return Ok(None)
+        }
+
+        Ok(Mcn::new(&code))
+    }
+
+    /// Get the Media Catalog Number of the current disc.
+    ///
+    /// Many discs do not contain this information.
+    #[deprecated = "use `media_catalog`, which returns `None` for discs without an MCN"]
+    pub fn mcn(&mut self) -> Option<String> {
+        let mut buffer = [0u8; 14];
+
+        unsafe {
+            self.ioctl(Operation::GetMcn, addr_of_mut!(buffer)).ok()?;
+        }
+
+        let string = String::from_utf8_lossy(&buffer[..buffer.len() - 1]).into_owned();
+        Some(string)
+    }
+
+    pub fn toc_header(&mut self) -> Result<TocHeader, CDRomError> {
+        let mut header = TocHeader::default();
+
+        if let Err(e) = unsafe {
+            self.ioctl(Operation::ReadTocHeader, addr_of_mut!(header))
+        } {
+            return Err(errno_to_error(e))
+        }
+
+        Ok(header)
+    }
+
+    /// Read the table of contents entry for a track.
+    pub fn toc_entry(&mut self, index: u8, address_type: AddressType) -> Result<TocEntry, CDRomError> {
+        let mut entry = _TocEntry {
+            track: index,
+            format: address_type as u8,
+            ..Default::default()
+        };
+
+        if let Err(e) = unsafe {
+            self.ioctl(Operation::ReadTocEntry, addr_of_mut!(entry))
+        } {
+            return Err(match e {
+                Errno::EINVAL => CDRomError::InvalidAddress,
+                _ => errno_to_error(e),
+            })
+        }
+
+        let entry = TocEntry {
+            track: entry.track,
+            adr: entry.adr_ctrl >> 4,
+            ctrl: entry.adr_ctrl & 0x0F,
+            addr: unsafe {
+                match entry.format {
+                    d if d == AddressType::Lba as u8 => Addr::Lba(entry.addr.lba),
+                    d if d == AddressType::Msf as u8 => Addr::Msf(entry.addr.msf),
+                    _ => return Err(CDRomError::ProtocolViolation)
+                }
+            },
+            datamode: entry.datamode,
+        };
+
+        Ok(entry)
+    }
+
+    /// Read the whole table of contents, with every track followed by the lead-out.
+    pub fn toc(&mut self, address_type: AddressType) -> Result<Vec<TocEntry>, CDRomError> {
+        let header = self.toc_header()?;
+
+        let mut entries = (header.first_track..=header.last_track)
+            .map(|i| self.toc_entry(i, address_type))
+            .collect::<Result<Vec<TocEntry>, CDRomError>>()?;
+        entries.push(self.leadout(address_type)?);
+
+        Ok(entries)
+    }
+
+    /// Get the table of contents entry for the lead-out, which starts right after the last track.
+    ///
+    /// The lead-out is stored in the table of contents as the special track number `0xAA`
+    /// ([`constants::CDROM_LEADOUT`]), so its address marks the end of the last track.
+    pub fn leadout(&mut self, address_type: AddressType) -> Result<TocEntry, CDRomError> {
+        self.toc_entry(constants::CDROM_LEADOUT, address_type)
+    }
+
+    /// Get the number of sectors on the disc, from LBA 0 up to the lead-out.
+    pub fn total_sectors(&mut self) -> Result<u32, CDRomError> {
+        let leadout = self.leadout(AddressType::Lba)?.addr.into_lba();
+
+        Ok(leadout.max(0) as u32)
+    }
+
+    /// Get the playing time of the disc, from LBA 0 up to the lead-out.
+    ///
+    /// Unlike the addresses in the table of contents, this is a duration, so it does
+    /// not include the 2 second offset of the first sector.
+    pub fn disc_length(&mut self) -> Result<Msf, CDRomError> {
+        let sectors = self.total_sectors()? as i32;
+
+        Ok(Msf::from_lba(sectors - constants::CD_MSF_OFFSET))
+    }
+
+    /// Get every track on the disc, including the last one, with their lengths.
+    ///
+    /// The ISRC is only read for audio tracks, and is `None` if the drive can't read it.
+    pub fn tracks(&mut self) -> Result<Vec<Track>, CDRomError> {
+        let entries = self.toc(AddressType::Lba)?;
+
+        Ok(self.tracks_from_toc(&entries, true))
+    }
+
+    /// Turn table of contents entries ending with the lead-out into tracks, reading
+    /// the ISRC of every audio track if `with_isrc` is set.
+    fn tracks_from_toc(&mut self, entries: &[TocEntry], with_isrc: bool) -> Vec<Track> {
+        entries
+            .windows(2)
+            .map(|pair| {
+                let (entry, next) = (pair[0], pair[1]);
+                let is_audio = entry.is_audio();
+
+                Track {
+                    number: entry.track,
+                    start: entry.addr,
+                    length_frames: (next.addr.into_lba() - entry.addr.into_lba()).max(0) as u32,
+                    is_audio,
+                    isrc: if is_audio && with_isrc {
+                        self.isrc(entry.track).ok().flatten()
+                    } else {
+                        None
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Generate a CUE sheet for a rip of the whole disc stored in `wav_filename`.
+    pub fn cue_sheet(&mut self, wav_filename: &str) -> Result<String, CDRomError> {
+        let tracks = self.tracks()?;
+        let pregaps = self.pregaps()?;
+
+        Ok(cue::generate_with_pregaps(wav_filename, &tracks, &pregaps))
+    }
+
+    /// Get the length in frames of the pregap before every track.
+    ///
+    /// The pregap of a track is the part at the end of the previous track marked as
+    /// index 0 of this one in the Q subchannel. Where the drive can read the raw Q
+    /// subchannel, the exact start of index 0 is found from it. Otherwise the pregaps
+    /// are estimated from the table of contents with [`cue::estimate_pregaps`], which
+    /// misses gaps between tracks of the same type.
+    ///
+    /// The pregap of the first track is everything before it, starting at LBA 0. Errors
+    /// other than the drive being unable to read the subchannel, like
+    /// [`CDRomError::NoDisc`], are returned.
+    pub fn pregaps(&mut self) -> Result<Vec<(u8, u32)>, CDRomError> {
+        let entries = self.toc(AddressType::Lba)?;
+        let tracks = self.tracks_from_toc(&entries, false);
+        let mut pregaps = cue::estimate_pregaps(&tracks);
+
+        for (i, pair) in tracks.windows(2).enumerate() {
+            let (previous, track) = (&pair[0], &pair[1]);
+
+            match self.find_pregap_start(previous.start.into_lba(), track) {
+                Ok(Some(start)) => pregaps[i + 1].1 = (track.start.into_lba() - start) as u32,
+                Ok(None) => (),
+                // The drive can't read the subchannel, so keep the estimates
+                Err(CDRomError::Unsupported | CDRomError::Sense { key: 0x05, .. }) => break,
+                // Or not around this track, so keep its estimate
+                Err(CDRomError::InvalidAddress) => (),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(pregaps)
+    }
+
+    /// Binary search between the start of the previous track and the start of `track`
+    /// for the first frame the Q subchannel says belongs to `track`.
+    ///
+    /// Returns `None` if the Q subchannel couldn't be read around some frame.
+    fn find_pregap_start(&mut self, previous_start: i32, track: &Track) -> Result<Option<i32>, CDRomError> {
+        // `low` is always in the previous track and `high` is always in this one
+        let (mut low, mut high) = (previous_start, track.start.into_lba());
+
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+
+            // Not every frame holds a position, so try the next few as well
+            let mut number = None;
+            for lba in middle..(middle + 3).min(high) {
+                let q = self.read_q_subchannel(Addr::Lba(lba))?;
+                if let Some(position) = q.is_valid().then(|| q.position()).flatten() {
+                    number = Some(position.trk);
+                    break
+                }
+            }
+
+            match number {
+                Some(n) if n >= track.number => high = middle,
+                Some(_) => low = middle,
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(high))
+    }
+
+    /// Compute the MusicBrainz Disc ID of the current disc from its table of contents.
+    pub fn musicbrainz_disc_id(&mut self) -> Result<String, CDRomError> {
+        let header = self.toc_header()?;
+
+        let offsets = (header.first_track..=header.last_track)
+            .map(|i| self.toc_entry(i, AddressType::Lba).map(|e| e.addr.into_lba()))
+            .collect::<Result<Vec<i32>, CDRomError>>()?;
+        let leadout = self.leadout(AddressType::Lba)?.addr.into_lba();
+
+        Ok(musicbrainz::disc_id(header.first_track, header.last_track, leadout, &offsets))
+    }
+
+    /// Compute a 64-bit fingerprint of the disc from its table of contents.
+    ///
+    /// The fingerprint covers the track numbers, the start of every track, and the
+    /// lead-out, so every copy of the same pressing has the same fingerprint no matter
+    /// how scratched it is. It is stable between versions of this crate, so it can be
+    /// used as a local cache key for metadata and AccurateRip results.
+    pub fn fingerprint(&mut self) -> Result<u64, CDRomError> {
+        let entries = self.toc(AddressType::Lba)?;
+
+        let mut hasher = sha1_smol::Sha1::new();
+        for entry in &entries {
+            hasher.update(&[entry.track]);
+            hasher.update(&entry.addr.into_lba().to_be_bytes());
+        }
+
+        let digest = hasher.digest().bytes();
+        Ok(u64::from_be_bytes(digest[..8].try_into().unwrap()))
+    }
+
+    /// Detect hidden track one audio (HTOA) in the pregap before the first track.
+    ///
+    /// Returns the address the hidden audio starts at and its length in sectors,
+    /// or `None` if the pregap is just silence.
+    pub fn htoa(&mut self) -> Result<Option<(Addr, usize)>, CDRomError> {
+        let header = self.toc_header()?;
+        let first_start = self.toc_entry(header.first_track, AddressType::Lba)?.addr.into_lba();
+
+        // Every disc has 150 sectors of silence before LBA 0, which can't be read.
+        // Anything more than that is a longer pregap that may contain audio.
+        if first_start <= 0 {
+            return Ok(None)
+        }
+
+        let mut buf = vec![0i16; 75 * constants::CD_FRAMESIZE_RAW as usize / 2];
+        let mut lba = 0;
+        while lba < first_start {
+            let frames = (first_start - lba).min(75) as usize;
+            let len = frames * constants::CD_FRAMESIZE_RAW as usize / 2;
+            self.read_audio_into(Addr::Lba(lba), frames, &mut buf[..len])?;
+
+            if buf[..len].iter().any(|s| *s != 0) {
+                return Ok(Some((Addr::Lba(0), first_start as usize)))
+            }
+
+            lba += frames as i32;
+        }
+
+        Ok(None)
+    }
+
+    /// Get the start of the last session on the disc, and whether it has more than one.
+    ///
+    /// On a mixed audio and data disc, the data session starts at the returned address.
+    pub fn multisession(&mut self, address_type: AddressType) -> Result<MultiSession, CDRomError> {
+        let mut ms = _MultiSession {
+            addr_format: address_type as u8,
+            ..Default::default()
+        };
+
+        if let Err(e) = unsafe {
+            self.ioctl(Operation::MultiSession, addr_of_mut!(ms))
+        } {
+            return Err(errno_to_error(e))
+        }
+
+        Ok(MultiSession {
+            addr: unsafe {
+                match ms.addr_format {
+                    d if d == AddressType::Lba as u8 => Addr::Lba(ms.addr.lba),
+                    d if d == AddressType::Msf as u8 => Addr::Msf(ms.addr.msf),
+                    _ => return Err(CDRomError::ProtocolViolation)
+                }
+            },
+            is_multisession: ms.xa_flag != 0,
+        })
+    }
+
+    /// Lock or unlock the drive's door.
+    ///
+    /// Drives that don't support the kernel ioctl are locked with
+    /// [`CDRom::prevent_removal`] instead.
+    pub fn set_lock(&mut self, locked: bool) -> Result<(), CDRomError> {
+        let result = match unsafe {
+            self.ioctl_int(Operation::LockDoor, locked as i32)
+        } {
+            Ok(d) if d == constants::EDRIVE_CANT_DO_THIS => Err(CDRomError::Unsupported),
+            Ok(_) => Ok(()),
+            Err(e) => match e {
+                d if d as i32 == constants::EDRIVE_CANT_DO_THIS => Err(CDRomError::Unsupported),
+                _ => Err(errno_to_error(e)),
+            },
+        };
+
+        match result {
+            Err(CDRomError::Unsupported) => self.prevent_removal(locked),
+            r => r,
+        }
+    }
+
+    /// Prevent or allow removal of the disc with the MMC PREVENT ALLOW MEDIUM REMOVAL
+    /// command, which locks the door on drives that ignore [`CDRom::set_lock`].
+    pub fn prevent_removal(&mut self, prevent: bool) -> Result<(), CDRomError> {
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::PreventAllowMediumRemoval as u8;
+        cdb[4] = prevent as u8;
+
+        self.packet_command(cdb, &mut [], DataDirection::None)
+    }
+
+    /// Eject the disc from the drive.
+    ///
+    /// Returns [`CDRomError::DoorLocked`] if the door is locked.
+    pub fn eject(&mut self) -> Result<(), CDRomError> {
+        let status = match unsafe {
+            self.ioctl_none(Operation::Eject)
+        } {
+            Ok(v) => v,
+            Err(e) => match e {
+                Errno::EBUSY => return Err(CDRomError::DoorLocked),
+                _ => return Err(errno_to_error(e)),
+            },
+        };
+
+        match status {
+            0 => Ok(()),
+            _ => Err(errno_to_error(Errno::from_raw(status))),
+        }
+    }
+
+    /// Unlock the door and eject the disc.
+    ///
+    /// The door is unlocked with both the kernel ioctl and the MMC PREVENT ALLOW MEDIUM
+    /// REMOVAL command, since some drives ignore one of them. [`CDRomError::DoorLocked`]
+    /// is only returned if the drive still refuses to eject after that.
+    pub fn eject_forced(&mut self) -> Result<(), CDRomError> {
+        // Either way of unlocking may be unsupported, so only the eject itself can fail
+        let _ = self.set_lock(false);
+        let _ = self.prevent_removal(false);
+
+        self.eject()
+    }
+
+    /// Hard-reset the drive, which can recover it after a storm of failed reads.
+    ///
+    /// This requires the `CAP_SYS_ADMIN` capability, and fails with `EACCES` otherwise.
+    /// The state of the drive is undefined right after a reset, so callers should check
+    /// [`CDRom::status`] before doing anything else.
+    pub fn reset(&mut self) -> Result<(), CDRomError> {
+        match unsafe {
+            self.ioctl_none(Operation::Reset)
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => Err(errno_to_error(e)),
+        }
+    }
+
+    /// Turn the kernel CD-ROM driver's debug messages on or off, which are written to
+    /// the kernel log.
+    ///
+    /// The setting applies to every drive on the system. Like [`CDRom::reset`], this
+    /// requires the `CAP_SYS_ADMIN` capability, and fails with `EACCES` otherwise.
+    pub fn set_debug(&mut self, enabled: bool) -> Result<(), CDRomError> {
+        match unsafe {
+            self.ioctl_int(Operation::Debug, enabled as i32)
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => match e {
+                // Kernels built without the debug ioctl don't recognise it
+                Errno::ENOTTY | Errno::EINVAL => Err(CDRomError::Unsupported),
+                _ => Err(errno_to_error(e)),
+            },
+        }
+    }
+
+    /// Enable or disable automatically ejecting the disc when the last process
+    /// using the drive closes it.
+    pub fn set_auto_eject(&mut self, enabled: bool) -> Result<(), CDRomError> {
+        match unsafe {
+            self.ioctl_int(Operation::EjectSoftware, enabled as i32)
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => Err(errno_to_error(e)),
+        }
+    }
+
+    /// Set options controlling how the kernel driver handles the drive, leaving any
+    /// others that are already set alone.
+    ///
+    /// Returns every option that is set afterwards. Calling this with no options
+    /// just returns the current options.
+    pub fn set_options(&mut self, options: CdOptions) -> Result<CdOptions, CDRomError> {
+        self.change_options(Operation::SetOptions, options)
+    }
+
+    /// Clear options controlling how the kernel driver handles the drive, such as
+    /// [`CdOptions::AUTO_EJECT`] so the tray doesn't open in the middle of a batch of rips.
+    ///
+    /// Returns every option that is still set afterwards.
+    pub fn clear_options(&mut self, options: CdOptions) -> Result<CdOptions, CDRomError> {
+        self.change_options(Operation::ClearOptions, options)
+    }
+
+    fn change_options(&mut self, op: Operation, options: CdOptions) -> Result<CdOptions, CDRomError> {
+        match unsafe {
+            self.ioctl_int(op, options.0)
+        } {
+            Ok(current) => Ok(CdOptions(current)),
+            Err(e) => Err(errno_to_error(e)),
+        }
+    }
+
+    /// Close the drive's tray.
+    ///
+    /// Slot loading drives and most laptop drives can't do this, and return
+    /// [`CDRomError::Unsupported`].
+    pub fn close(&mut self) -> Result<(), CDRomError> {
+        let status = match unsafe {
+            self.ioctl_none(Operation::CloseTray)
+        } {
+            Ok(v) => v,
+            Err(e) => match e {
+                Errno::EBUSY => return Err(CDRomError::DoorLocked),
+                _ => return Err(errno_to_error(e)),
+            },
+        };
+
+        match status {
+            0 => Ok(()),
+            _ => Err(errno_to_error(Errno::from_raw(status))),
+        }
+    }
+
+    /// Set the read speed of the drive.
+    ///
+    /// The drive expects speeds as a multiple of 1x, where 1x is 176 KB/s for audio
+    /// ([`constants::CD_SPEED_1X_KBPS`]), so the requested speed is rounded down to the
+    /// nearest multiple, with a minimum of 1x. A speed of `0` selects the maximum speed.
+    pub fn set_speed(&mut self, speed_kbps: u16) -> Result<(), CDRomError> {
+        let factor = match speed_kbps {
+            0 => 0,
+            s => (s / constants::CD_SPEED_1X_KBPS).max(1),
+        };
+
+        match unsafe {
+            self.ioctl_int(Operation::SelectSpeed, factor as i32)
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => match e {
+                Errno::ENOSYS | Errno::EOPNOTSUPP | Errno::EINVAL => Err(CDRomError::Unsupported),
+                _ => Err(errno_to_error(e)),
+            },
+        }
+    }
+
+    /// Get the read speeds in KB/s the drive advertises, from slowest to fastest, so only
+    /// speeds [`CDRom::set_speed`] and [`CDRom::set_streaming`] will honour are offered.
+    ///
+    /// The speeds are the start and end speeds of the nominal read performance from
+    /// [`CDRom::get_performance`], along with the maximum read speed from the CD
+    /// capabilities mode page. The write speed descriptors in that page are left out,
+    /// since drives often write slower than they read. A drive without GET PERFORMANCE
+    /// only reports its maximum read speed.
+    pub fn supported_speeds(&mut self) -> Result<Vec<u16>, CDRomError> {
+        let page = self.mode_sense(constants::MODE_PAGE_CAPABILITIES)?;
+        if page.len() < 10 {
+            return Err(CDRomError::ProtocolViolation)
+        }
+
+        let max_read = u16::from_be_bytes([page[8], page[9]]);
+
+        let performance = match self.get_performance() {
+            Ok(p) => p,
+            Err(CDRomError::Unsupported | CDRomError::Sense { key: 0x05, .. }) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        let mut speeds: Vec<u16> = performance
+            .iter()
+            .filter_map(|d| match *d {
+                PerfDescriptor::Nominal { start_kbps, end_kbps, .. } => Some([start_kbps, end_kbps]),
+                PerfDescriptor::Exception { .. } => None,
+            })
+            .flatten()
+            .map(|s| s.min(u16::MAX as u32) as u16)
+            .filter(|s| *s > 0 && (max_read == 0 || *s <= max_read))
+            .collect();
+        if max_read > 0 {
+            speeds.push(max_read);
+        }
+
+        speeds.sort_unstable();
+        speeds.dedup();
+
+        Ok(speeds)
+    }
+
+    /// Set the read speed of the drive with the MMC SET STREAMING command.
+    ///
+    /// Many modern drives ignore [`CDRom::set_speed`], but honour this. The speed is given
+    /// in KB/s, and applies to the whole disc. If the drive rejects the command, this falls
+    /// back to [`CDRom::set_speed`].
+    pub fn set_streaming(&mut self, read_kbps: u32) -> Result<(), CDRomError> {
+        // The performance descriptor covers everything up to the lead-out
+        let end_lba = match self.leadout(AddressType::Lba) {
+            Ok(entry) => (entry.addr.into_lba() - 1).max(0) as u32,
+            Err(_) => u32::MAX,
+        };
+
+        let mut descriptor = [0u8; 28];
+        descriptor[8..12].copy_from_slice(&end_lba.to_be_bytes());
+        // Read and write sizes in KB, over a time in milliseconds
+        descriptor[12..16].copy_from_slice(&read_kbps.to_be_bytes());
+        descriptor[16..20].copy_from_slice(&1000u32.to_be_bytes());
+        descriptor[20..24].copy_from_slice(&read_kbps.to_be_bytes());
+        descriptor[24..28].copy_from_slice(&1000u32.to_be_bytes());
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::SetStreaming as u8;
+        cdb[9..11].copy_from_slice(&(descriptor.len() as u16).to_be_bytes());
+
+        match self.packet_command(cdb, &mut descriptor, DataDirection::Write) {
+            Ok(_) => Ok(()),
+            Err(CDRomError::Unsupported | CDRomError::Sense { key: 0x05, .. }) => {
+                self.set_speed(read_kbps.min(u16::MAX as u32) as u16)
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Set how many frames the driver buffers while reading audio.
+    ///
+    /// Only a few drivers make use of this; for every other driver this returns
+    /// [`CDRomError::Unsupported`].
+    pub fn set_audio_buffer_size(&mut self, frames: u32) -> Result<(), CDRomError> {
+        match unsafe {
+            self.ioctl_int(Operation::AudioBufferSize, frames.min(i32::MAX as u32) as i32)
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => match e {
+                Errno::ENOSYS | Errno::EOPNOTSUPP | Errno::ENOTTY | Errno::EINVAL => Err(CDRomError::Unsupported),
+                _ => Err(errno_to_error(e)),
+            },
+        }
+    }
+
+    /// Play audio through the drive's audio output, from `start` up to `end`.
+    pub fn play_msf(&mut self, start: Msf, end: Msf) -> Result<(), CDRomError> {
+        if start > end {
+            return Err(CDRomError::InvalidAddress)
+        }
+
+        let msf = MsfLong {
+            min0: start.minute,
+            sec0: start.second,
+            frame0: start.frame,
+            min1: end.minute,
+            sec1: end.second,
+            frame1: end.frame,
+        };
+
+        match unsafe {
+            self.ioctl(Operation::PlayMsf, &msf)
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => match e {
+                Errno::EINVAL => Err(CDRomError::InvalidAddress),
+                _ => Err(errno_to_error(e)),
+            },
+        }
+    }
+
+    /// Play `length` sectors of audio through the drive's audio output, starting at
+    /// `start_lba`.
+    ///
+    /// A single call can play at most 65535 sectors, which is about 14 and a half minutes.
+    pub fn play_block(&mut self, start_lba: i32, length: i32) -> Result<(), CDRomError> {
+        if start_lba < 0 || !(1..=u16::MAX as i32).contains(&length) {
+            return Err(CDRomError::InvalidAddress)
+        }
+
+        let blk = Blk {
+            from: start_lba as u32,
+            len: length as u16,
+        };
+
+        match unsafe {
+            self.ioctl(Operation::PlayBlock, &blk)
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => match e {
+                Errno::EINVAL => Err(CDRomError::InvalidAddress),
+                _ => Err(errno_to_error(e)),
+            },
+        }
+    }
+
+    /// Play audio through the drive's audio output, from the start of `start_track`
+    /// through the end of `end_track`.
+    pub fn play_track_index(&mut self, start_track: u8, end_track: u8) -> Result<(), CDRomError> {
+        if start_track > end_track {
+            return Err(CDRomError::InvalidAddress)
+        }
+
+        let ti = TrackIndex {
+            trk0: start_track,
+            ind0: 1,
+            trk1: end_track,
+            ind1: 1,
+        };
+
+        match unsafe {
+            self.ioctl(Operation::PlayTrackIndex, &ti)
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => match e {
+                Errno::EINVAL => Err(CDRomError::InvalidAddress),
+                _ => Err(errno_to_error(e)),
+            },
+        }
+    }
+
+    /// Pause audio playback started by [`CDRom::play_msf`] or [`CDRom::play_track_index`].
+    pub fn pause(&mut self) -> Result<(), CDRomError> {
+        match unsafe {
+            self.ioctl_none(Operation::Pause)
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => Err(errno_to_error(e)),
+        }
+    }
+
+    /// Resume audio playback paused by [`CDRom::pause`].
+    pub fn resume(&mut self) -> Result<(), CDRomError> {
+        match unsafe {
+            self.ioctl_none(Operation::Resume)
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => Err(errno_to_error(e)),
+        }
+    }
+
+    /// Fast forward or rewind audio playback from `start`, playing short bursts of audio
+    /// as it goes, like the seek buttons on a CD player.
+    ///
+    /// Scanning continues until playback is stopped or paused with [`CDRom::stop`] or
+    /// [`CDRom::pause`], or restarted with [`CDRom::play_msf`].
+    pub fn scan(&mut self, start: Addr, direction: ScanDirection) -> Result<(), CDRomError> {
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::Scan as u8;
+        cdb[1] = (direction as u8) << 4;
+        match start {
+            Addr::Lba(lba) => cdb[2..6].copy_from_slice(&lba.to_be_bytes()),
+            Addr::Msf(msf) => {
+                if msf.invalid() {
+                    return Err(CDRomError::InvalidAddress)
+                }
+
+                cdb[3..6].copy_from_slice(&[msf.minute, msf.second, msf.frame]);
+                // The scan starting address is an MSF address
+                cdb[9] = 0x40;
+            },
+        }
+
+        self.packet_command(cdb, &mut [], DataDirection::None)
+    }
+
+    /// Stop audio playback and spin down the disc.
+    ///
+    /// Unlike [`CDRom::pause`], playback cannot be resumed afterwards. Drives that don't
+    /// support the kernel ioctl are stopped with [`CDRom::stop_play_scan`] instead, which
+    /// leaves the disc spinning.
+    pub fn stop(&mut self) -> Result<(), CDRomError> {
+        match unsafe {
+            self.ioctl_none(Operation::Stop)
+        } {
+            Ok(_) => Ok(()),
+            Err(Errno::ENOTTY | Errno::EINVAL) => self.stop_play_scan(),
+            Err(e) => match errno_to_error(e) {
+                CDRomError::Unsupported => self.stop_play_scan(),
+                e => Err(e),
+            },
+        }
+    }
+
+    /// Stop audio playback or a scan with the MMC STOP PLAY/SCAN command.
+    ///
+    /// This works on some drives that ignore [`CDRom::stop`], and doesn't spin down the disc.
+    pub fn stop_play_scan(&mut self) -> Result<(), CDRomError> {
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::StopPlayScan as u8;
+
+        self.packet_command(cdb, &mut [], DataDirection::None)
+    }
+
+    /// Get the volume of the drive's analog audio output.
+    pub fn volume(&mut self) -> Result<Volume, CDRomError> {
+        let mut volume = Volume::default();
+
+        match unsafe {
+            self.ioctl(Operation::VolumeRead, addr_of_mut!(volume))
+        } {
+            Ok(_) => Ok(volume),
+            Err(e) => Err(errno_to_error(e)),
+        }
+    }
+
+    /// Set the volume of the drive's analog audio output, which is used during
+    /// playback with [`CDRom::play_msf`] or [`CDRom::play_track_index`].
+    pub fn set_volume(&mut self, volume: Volume) -> Result<(), CDRomError> {
+        match unsafe {
+            self.ioctl(Operation::VolumeControl, &volume)
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => Err(errno_to_error(e)),
+        }
+    }
+
+    /// Read the current subchannel Q position, with the addresses in MSF format.
+    pub fn subchannel_msf(&mut self) -> Result<SubChannel, CDRomError> {
+        self.subchannel(AddressType::Msf)
+    }
+
+    /// Read the current subchannel Q position, with the addresses in the requested format.
+    pub fn subchannel(&mut self, address_type: AddressType) -> Result<SubChannel, CDRomError> {
+        let mut argument = _SubChannel {
+            format: address_type as u8,
+            ..Default::default()
+        };
+
+        unsafe {
+            self.ioctl(Operation::SubChannel, addr_of_mut!(argument)).map_err(errno_to_error)?;
+        }
+
+        Ok(SubChannel {
+            audiostatus: argument.audiostatus,
+            adr: argument.adr_ctrl >> 4,
+            ctrl: argument.adr_ctrl & 0x0F,
+            trk: argument.trk,
+            ind: argument.ind,
+            absaddr: unsafe {
+                match argument.format {
+                    d if d == AddressType::Lba as u8 => Addr::Lba(argument.absaddr.lba),
+                    d if d == AddressType::Msf as u8 => Addr::Msf(argument.absaddr.msf),
+                    _ => return Err(CDRomError::ProtocolViolation)
+                }
+            },
+            reladdr: unsafe {
+                match argument.format {
+                    d if d == AddressType::Lba as u8 => Addr::Lba(argument.reladdr.lba),
+                    d if d == AddressType::Msf as u8 => Addr::Msf(argument.reladdr.msf),
+                    _ => return Err(CDRomError::ProtocolViolation)
+                }
+            }
+        })
+    }
+
+    /// Get the International Standard Recording Code of a track.
+    ///
+    /// Returns `None` if the track does not have an ISRC.
+    pub fn isrc(&mut self, track: u8) -> Result<Option<String>, CDRomError> {
+        let mut buffer = [0u8; 24];
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::ReadSubchannel as u8;
+        // Request Q subchannel data
+        cdb[2] = 0x40;
+        // Sub-channel data format: ISRC
+        cdb[3] = 0x03;
+        cdb[6] = track;
+        cdb[8] = buffer.len() as u8;
+
+        self.packet_command(cdb, &mut buffer, DataDirection::Read)?;
+
+        // The TCVal bit signals that the ISRC is valid
+        if buffer[8] & 0x80 == 0 {
+            return Ok(None)
+        }
+
+        let isrc = String::from_utf8_lossy(&buffer[9..21]).into_owned();
+        Ok(Some(isrc))
+    }
+
+    /// Read the CD-TEXT information stored in the lead-in of the disc.
+    ///
+    /// Returns `None` if the disc does not contain any CD-TEXT.
+    pub fn cd_text(&mut self) -> Result<Option<CdText>, CDRomError> {
+        let data = match self.read_toc_raw(TocFormat::CdText) {
+            Ok(d) => d,
+            // Most drives reject the command outright when there is no CD-TEXT
+            Err(CDRomError::ReadError { .. } | CDRomError::Errno(Errno::EIO)) => return Ok(None),
+            Err(CDRomError::Sense { key: 0x05, .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        Ok(CdText::from_packs(&data))
+    }
+
+    /// Read the complete response to a READ TOC/PMA/ATIP command in the given format,
+    /// including the 4 byte header.
+    ///
+    /// Unlike [`CDRom::toc_entry`], this includes every field the drive returns, such as
+    /// the session numbers in [`TocFormat::FullToc`]. Addresses are returned as LBA.
+    pub fn read_toc_raw(&mut self, format: TocFormat) -> Result<Vec<u8>, CDRomError> {
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::ReadTocPmaAtip as u8;
+        cdb[2] = format as u8;
+
+        self.read_sized_response(cdb, 4, 2)
+    }
+
+    /// Read the whole table of contents with a single READ TOC command, including the lead-out.
+    ///
+    /// This is the parsed form of [`TocFormat::Toc`], with every address as LBA.
+    pub fn read_toc(&mut self) -> Result<Vec<TocEntry>, CDRomError> {
+        let data = self.read_toc_raw(TocFormat::Toc)?;
+
+        let entries = data[4..]
+            .chunks_exact(8)
+            .map(|d| TocEntry {
+                track: d[2],
+                adr: d[1] >> 4,
+                ctrl: d[1] & 0x0F,
+                addr: Addr::Lba(i32::from_be_bytes([d[4], d[5], d[6], d[7]])),
+                // Filled in the same way the kernel does for `toc_entry`
+                datamode: (d[1] & 0x04 != 0) as u8,
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Read audio from the CD.
+    ///
+    /// This method is a convenience method around [`CDRom::read_audio_into`].
+    pub fn read_audio(&mut self, address: Addr, frames: usize) -> Result<Vec<i16>, CDRomError> {
+        let mut buf = vec![0i16; (frames * constants::CD_FRAMESIZE_RAW as usize) / 2];
+
+        self.read_audio_into(address, frames, &mut buf)?;
+
+        Ok(buf)
+    }
+
+    /// Read audio from the CD as little-endian bytes, the byte order CD audio and WAV
+    /// files use, whatever the byte order of the host.
+    pub fn read_audio_le_bytes(&mut self, address: Addr, frames: usize) -> Result<Vec<u8>, CDRomError> {
+        let samples = self.read_audio(address, frames)?;
+
+        // The samples hold the bytes exactly as the drive returned them
+        Ok(samples.iter().flat_map(|s| s.to_ne_bytes()).collect())
+    }
+
+    /// Read audio from the CD into a preallocated buffer.
+    ///
+    /// The buffer must be large enough to hold the audio for all the frames you want to read.
+    /// Since the values are [`i16`]s, the equation for the buffer size is `(n_frames * 2352) / 2`
+    ///
+    /// The samples are the little-endian bytes returned by the drive, reinterpreted in the
+    /// byte order of the host. On a big-endian host every sample is byte-swapped, so convert
+    /// them with [`i16::from_le`], or use [`CDRom::read_audio_le_bytes`] to get bytes that
+    /// can be written to a WAV file as they are.
+    pub fn read_audio_into(&mut self, address: Addr, frames: usize, buf: &mut [i16]) -> Result<(), CDRomError> {
+        let (addr, addr_format) = match address {
+            Addr::Lba(lba) => (AddrUnion { lba }, AddressType::Lba),
+            Addr::Msf(msf) => {
+                if msf.invalid() {
+                    return Err(CDRomError::InvalidAddress)
+                }
+
+                (AddrUnion { msf }, AddressType::Msf)
+            },
+        };
+
+        if !(1..=75).contains(&frames) {
+            panic!("Invalid number of frames!")
+        }
+
+        if buf.len() < (frames * constants::CD_FRAMESIZE_RAW as usize) / 2 {
+            panic!("Buffer is too small!")
+        }
+
+        let mut ra = ReadAudio {
+            addr,
+            addr_format,
+            nframes: frames as i32,
+            buf: buf.as_mut_ptr()
+        };
+
+        let status = match unsafe {
+            self.ioctl(Operation::ReadAudio, addr_of_mut!(ra))
+        } {
+            Ok(v) => v,
+            // Some drives only support reading audio with READ CD
+            Err(Errno::ENOSYS | Errno::EOPNOTSUPP) => {
+                let mut bytes = vec![0u8; frames * constants::CD_FRAMESIZE_RAW as usize];
+                self.read_cd(address, frames, SectorType::CdDa, false, &mut bytes).map_err(|e| e.at(address))?;
+
+                for (sample, b) in buf.iter_mut().zip(bytes.chunks_exact(2)) {
+                    *sample = i16::from_ne_bytes([b[0], b[1]]);
+                }
+
+                return Ok(())
+            },
+            Err(e) => return Err(errno_to_error(e).at(address)),
+        };
+
+        if status != 0 {
+            return Err(errno_to_error(Errno::from_raw(status)).at(address));
+        }
+
+        Ok(())
+    }
+
+    /// Read audio like [`CDRom::read_audio_into`], retrying up to `attempts` times in total
+    /// when the drive fails to read the sectors.
+    ///
+    /// Returns the number of retries that were needed. Errors other than read failures,
+    /// such as [`CDRomError::NoDisc`], are returned straight away.
+    pub fn read_audio_retry(&mut self, address: Addr, frames: usize, buf: &mut [i16], attempts: u32) -> Result<u32, CDRomError> {
+        self.read_audio_attempts(address, frames, buf, attempts, false)
+    }
+
+    /// Read audio like [`CDRom::read_audio_retry`], but make sure every attempt is read
+    /// from the disc rather than the drive's cache.
+    ///
+    /// Before each attempt, the cache is flushed with [`CDRom::flush_cache`] and a sector
+    /// far away from `address` is read, so the drive has to seek back to read the audio
+    /// again. This makes re-reads of damaged sectors genuine, at the cost of a seek each.
+    pub fn read_audio_uncached(&mut self, address: Addr, frames: usize, buf: &mut [i16], attempts: u32) -> Result<u32, CDRomError> {
+        self.read_audio_attempts(address, frames, buf, attempts, true)
+    }
+
+    fn read_audio_attempts(
+        &mut self,
+        address: Addr,
+        frames: usize,
+        buf: &mut [i16],
+        attempts: u32,
+        uncached: bool,
+    ) -> Result<u32, CDRomError> {
+        let mut retries = 0;
+
+        loop {
+            if uncached {
+                self.defeat_cache(address.into_lba(), frames);
+            }
+
+            match self.read_audio_into(address, frames, buf) {
+                Ok(_) => return Ok(retries),
+                Err(CDRomError::ReadError { .. } | CDRomError::Errno(Errno::EIO) | CDRomError::Sense { key: 0x03, .. })
+                    if retries + 1 < attempts => retries += 1,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Evict the sectors from `lba` for `frames` frames from the drive's cache, by flushing
+    /// it and reading a single sector far away. Errors are ignored, since the drive may not
+    /// support flushing and the far sector may be past the end of the disc.
+    fn defeat_cache(&mut self, lba: i32, frames: usize) {
+        // Further than the read-ahead of any drive's cache
+        const DISTANCE: i32 = 10_000;
+
+        let far = if lba >= DISTANCE { lba - DISTANCE } else { lba + frames as i32 + DISTANCE };
+        let mut sector = [0i16; constants::CD_FRAMESIZE_RAW as usize / 2];
+
+        let _ = self.flush_cache();
+        let _ = self.read_audio_into(Addr::Lba(far), 1, &mut sector);
+    }
+
+    /// Read audio with every frame read twice, to catch errors the drive doesn't report.
+    ///
+    /// Frames where the two reads differ are read again, one at a time, until two of the
+    /// reads agree. If that doesn't happen within 16 reads, [`CDRomError::ReadError`] is
+    /// returned. The counts of frames read and re-read are added to `stats`.
+    pub fn read_audio_verified(&mut self, address: Addr, frames: usize, stats: &mut VerifyStats) -> Result<Vec<i16>, CDRomError> {
+        const MAX_READS: usize = 16;
+        let frame_values = constants::CD_FRAMESIZE_RAW as usize / 2;
+
+        let start = address.into_lba();
+        let mut samples = vec![0i16; frames * frame_values];
+        let mut check = vec![0i16; 75 * frame_values];
+
+        for (chunk_index, chunk) in samples.chunks_mut(75 * frame_values).enumerate() {
+            let chunk_start = start + chunk_index as i32 * 75;
+            let chunk_frames = chunk.len() / frame_values;
+
+            self.read_audio_into(Addr::Lba(chunk_start), chunk_frames, chunk)?;
+            self.read_audio_into(Addr::Lba(chunk_start), chunk_frames, &mut check)?;
+
+            for (i, (frame, other)) in chunk
+                .chunks_exact_mut(frame_values)
+                .zip(check.chunks_exact(frame_values))
+                .enumerate()
+            {
+                stats.frames += 1;
+                if frame == other {
+                    continue
+                }
+                stats.mismatched_frames += 1;
+
+                let mut reads = vec![frame.to_vec(), other.to_vec()];
+                let agreed = loop {
+                    if reads.len() >= MAX_READS {
+                        return Err(CDRomError::ReadError { address: Some(Addr::Lba(chunk_start + i as i32)) })
+                    }
+
+                    let mut read = vec![0i16; frame_values];
+                    self.read_audio_into(Addr::Lba(chunk_start + i as i32), 1, &mut read)?;
+                    stats.rereads += 1;
+
+                    if reads.contains(&read) {
+                        break read
+                    }
+                    reads.push(read);
+                };
+
+                frame.copy_from_slice(&agreed);
+            }
+        }
+
+        Ok(samples)
+    }
+
+    /// Read sectors from the CD with the MMC READ CD command.
+    ///
+    /// Each sector takes up [`SectorType::sector_size`] bytes in `buf`. If `with_c2`
+    /// is set, each sector is followed by its 294 bytes of C2 error pointers.
+    pub fn read_cd(
+        &mut self,
+        start: Addr,
+        frames: usize,
+        sector_type: SectorType,
+        with_c2: bool,
+        buf: &mut [u8]
+    ) -> Result<(), CDRomError> {
+        if !(1..=0xFFFFFF).contains(&frames) {
+            return Err(CDRomError::InvalidAddress)
+        }
+
+        let mut sector_size = sector_type.sector_size();
+        if with_c2 {
+            sector_size += constants::CD_C2_SIZE as usize;
+        }
+
+        let needed = frames * sector_size;
+        if buf.len() < needed {
+            return Err(CDRomError::InvalidBufferSize(needed, buf.len()))
+        }
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::ReadCd as u8;
+        cdb[1] = (sector_type as u8) << 2;
+        cdb[2..6].copy_from_slice(&start.into_lba().to_be_bytes());
+        cdb[6..9].copy_from_slice(&(frames as u32).to_be_bytes()[1..]);
+        cdb[9] = read_cd_fields(sector_type, with_c2);
+
+        self.packet_command(cdb, &mut buf[..needed], DataDirection::Read)
+    }
+
+    /// Read the sectors from `start` up to, but not including, `end` with the MMC READ
+    /// CD MSF command.
+    ///
+    /// This is the same as [`CDRom::read_cd`] without C2 error pointers, but addresses
+    /// the sectors by MSF, which some drives handle better for audio.
+    pub fn read_cd_msf(
+        &mut self,
+        start: Msf,
+        end: Msf,
+        sector_type: SectorType,
+        buf: &mut [u8]
+    ) -> Result<(), CDRomError> {
+        if start.invalid() || end.invalid() || end.to_lba() <= start.to_lba() {
+            return Err(CDRomError::InvalidAddress)
+        }
+
+        let frames = (end.to_lba() - start.to_lba()) as usize;
+        let needed = frames * sector_type.sector_size();
+        if buf.len() < needed {
+            return Err(CDRomError::InvalidBufferSize(needed, buf.len()))
+        }
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::ReadCdMsf as u8;
+        cdb[1] = (sector_type as u8) << 2;
+        cdb[3..6].copy_from_slice(&[start.minute, start.second, start.frame]);
+        cdb[6..9].copy_from_slice(&[end.minute, end.second, end.frame]);
+        cdb[9] = read_cd_fields(sector_type, false);
+
+        self.packet_command(cdb, &mut buf[..needed], DataDirection::Read)
+            .map_err(|e| e.at(Addr::Msf(start)))
+    }
+
+    /// Read the CD+G graphics stored in the R-W subchannels, in the `.cdg` file format.
+    ///
+    /// Each frame contributes 96 bytes, with the P and Q subchannel bits masked off. The
+    /// drive must be able to return raw subchannel data with READ CD, which requires
+    /// packet command support (see [`Capabilities::can_send_packets`]). Drives that
+    /// can't will return an error.
+    pub fn read_cdg(&mut self, start: Addr, frames: usize) -> Result<Vec<u8>, CDRomError> {
+        let sub_size = constants::CD_FRAMESIZE_SUB as usize;
+        let mut cdg = vec![0u8; frames * sub_size];
+
+        let mut lba = start.into_lba();
+        for chunk in cdg.chunks_mut(75 * sub_size) {
+            let n = chunk.len() / sub_size;
+
+            let mut cdb = [0u8; CDROM_PACKET_SIZE];
+            cdb[0] = GenericPacketCommand::ReadCd as u8;
+            cdb[2..6].copy_from_slice(&lba.to_be_bytes());
+            cdb[6..9].copy_from_slice(&(n as u32).to_be_bytes()[1..]);
+            // No main channel data, only raw P-W subchannel data
+            cdb[9] = 0x00;
+            cdb[10] = 0x01;
+
+            self.packet_command(cdb, chunk, DataDirection::Read)?;
+            lba += n as i32;
+        }
+
+        for byte in cdg.iter_mut() {
+            *byte &= 0x3F;
+        }
+
+        Ok(cdg)
+    }
+
+    /// Read the raw Q subchannel of a single frame, including its CRC.
+    ///
+    /// Like [`CDRom::read_cdg`], this needs a drive that can return raw subchannel data.
+    pub fn read_q_subchannel(&mut self, address: Addr) -> Result<QSubChannel, CDRomError> {
+        let mut raw = [0u8; constants::CD_FRAMESIZE_SUB as usize];
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::ReadCd as u8;
+        cdb[2..6].copy_from_slice(&address.into_lba().to_be_bytes());
+        cdb[8] = 1;
+        // No main channel data, only raw P-W subchannel data
+        cdb[10] = 0x01;
+
+        self.packet_command(cdb, &mut raw, DataDirection::Read).map_err(|e| e.at(address))?;
+
+        QSubChannel::from_pw(&raw).ok_or(CDRomError::ProtocolViolation)
+    }
+
+    /// Read any number of frames of audio from the CD into a preallocated buffer.
+    ///
+    /// Unlike [`CDRom::read_audio_into`], this is not limited to 75 frames at a time,
+    /// and reads in as many chunks as needed to fill the buffer.
+    pub fn read_audio_range(&mut self, start: Addr, frames: usize, buf: &mut [i16]) -> Result<(), CDRomError> {
+        let frame_len = constants::CD_FRAMESIZE_RAW as usize / 2;
+
+        if buf.len() < frames * frame_len {
+            return Err(CDRomError::InvalidBufferSize(frames * frame_len * 2, buf.len() * 2))
+        }
+
+        let mut lba = start.into_lba();
+        for chunk in buf[..frames * frame_len].chunks_mut(75 * frame_len) {
+            let n = chunk.len() / frame_len;
+            self.read_audio_into(Addr::Lba(lba), n, chunk)?;
+            lba += n as i32;
+        }
+
+        Ok(())
+    }
+
+    /// Read audio from the CD and write it to `out` as little-endian samples.
+    ///
+    /// The audio is read 25 frames at a time, so any number of frames can be read
+    /// without holding all of them in memory.
+    pub fn read_audio_to<W: Write>(&mut self, start: Addr, frames: usize, out: &mut W) -> Result<(), CDRomError> {
+        self.read_audio_to_with_progress(start, frames, 0, out, |_, _| ())
+    }
+
+    /// Like [`CDRom::read_audio_to`], but corrects for the drive's read offset as in
+    /// [`CDRom::read_audio_corrected`].
+    pub fn read_audio_to_corrected<W: Write>(
+        &mut self,
+        start: Addr,
+        frames: usize,
+        offset_samples: i32,
+        out: &mut W,
+    ) -> Result<(), CDRomError> {
+        self.read_audio_to_with_progress(start, frames, offset_samples, out, |_, _| ())
+    }
+
+    /// Rip an audio track, writing it to `out` as little-endian samples.
+    ///
+    /// `progress` is called with the number of frames done and the total after every
+    /// chunk is read, so it can drive a progress bar. Data tracks return
+    /// [`CDRomError::NotAudioCD`].
+    pub fn rip_track_with_progress<F: FnMut(u32, u32)>(
+        &mut self,
+        track: &Track,
+        out: &mut impl Write,
+        progress: F,
+    ) -> Result<(), CDRomError> {
+        self.rip_track_corrected_with_progress(track, 0, out, progress)
+    }
+
+    /// Like [`CDRom::rip_track_with_progress`], but corrects for the drive's read offset
+    /// as in [`CDRom::read_audio_corrected`].
+    pub fn rip_track_corrected_with_progress<F: FnMut(u32, u32)>(
+        &mut self,
+        track: &Track,
+        offset_samples: i32,
+        out: &mut impl Write,
+        progress: F,
+    ) -> Result<(), CDRomError> {
+        if !track.is_audio {
+            return Err(CDRomError::NotAudioCD)
+        }
+
+        self.read_audio_to_with_progress(track.start, track.length_frames as usize, offset_samples, out, progress)
+    }
+
+    fn read_audio_to_with_progress<W: Write, F: FnMut(u32, u32)>(
+        &mut self,
+        start: Addr,
+        frames: usize,
+        offset_samples: i32,
+        out: &mut W,
+        mut progress: F,
+    ) -> Result<(), CDRomError> {
+        const CHUNK_FRAMES: usize = 25;
+        let frame_len = constants::CD_FRAMESIZE_RAW as usize / 2;
+
+        // Only needed to know where to stop reading when the offset is corrected
+        let leadout = match offset_samples {
+            0 => None,
+            _ => Some(self.leadout(AddressType::Lba)?.addr.into_lba()),
+        };
+
+        let mut samples = vec![0i16; CHUNK_FRAMES * frame_len];
+        let mut bytes = Vec::with_capacity(CHUNK_FRAMES * constants::CD_FRAMESIZE_RAW as usize);
+
+        let mut lba = start.into_lba();
+        let mut remaining = frames;
+        while remaining > 0 {
+            let n = remaining.min(CHUNK_FRAMES);
+            let chunk = &mut samples[..n * frame_len];
+            match leadout {
+                None => self.read_audio_into(Addr::Lba(lba), n, chunk)?,
+                Some(leadout) => self.read_offset_window(lba, n, offset_samples, leadout, chunk)?,
+            }
+
+            bytes.clear();
+            // The samples hold the bytes exactly as the drive returned them
+            bytes.extend(chunk.iter().flat_map(|s| s.to_ne_bytes()));
+            out.write_all(&bytes)?;
+
+            lba += n as i32;
+            remaining -= n;
+            progress((frames - remaining) as u32, frames as u32);
+        }
+
+        Ok(())
+    }
+
+    /// Read audio from the CD along with its C2 error pointers.
+    ///
+    /// The second vector holds 294 bytes per frame, where each set bit marks a byte of
+    /// the audio the drive could not correct. The samples are host-endian like
+    /// [`CDRom::read_audio`].
+    pub fn read_audio_c2(&mut self, start: Addr, frames: usize) -> Result<(Vec<i16>, Vec<u8>), CDRomError> {
+        let audio_size = constants::CD_FRAMESIZE_RAW as usize;
+        let c2_size = constants::CD_C2_SIZE as usize;
+
+        let mut raw = vec![0u8; frames * (audio_size + c2_size)];
+        self.read_cd(start, frames, SectorType::CdDa, true, &mut raw)?;
+
+        let mut audio = Vec::with_capacity(frames * audio_size / 2);
+        let mut c2 = Vec::with_capacity(frames * c2_size);
+        for sector in raw.chunks_exact(audio_size + c2_size) {
+            let (samples, pointers) = sector.split_at(audio_size);
+
+            audio.extend(samples.chunks_exact(2).map(|b| i16::from_ne_bytes([b[0], b[1]])));
+            c2.extend_from_slice(pointers);
+        }
+
+        Ok((audio, c2))
+    }
+
+    /// Read audio from the CD, correcting for the drive's read offset.
+    ///
+    /// `offset_samples` is the drive's read offset in stereo samples, as listed in the
+    /// AccurateRip drive database. The audio is read starting that many samples after
+    /// `address` (or before it, for negative offsets), so the result lines up with what
+    /// was actually pressed on the disc. Any part of the window that falls before the
+    /// start of the disc or after the lead-out is filled with silence.
+    pub fn read_audio_corrected(&mut self, address: Addr, frames: usize, offset_samples: i32) -> Result<Vec<i16>, CDRomError> {
+        let leadout = self.leadout(AddressType::Lba)?.addr.into_lba();
+
+        let mut buf = vec![0i16; frames * constants::CD_FRAMESIZE_RAW as usize / 2];
+        self.read_offset_window(address.into_lba(), frames, offset_samples, leadout, &mut buf)?;
+
+        Ok(buf)
+    }
+
+    /// Fill `buf` with `frames` frames of audio starting `offset_samples` stereo samples
+    /// after `lba`, with silence for any part before LBA 0 or after `leadout`.
+    fn read_offset_window(
+        &mut self,
+        lba: i32,
+        frames: usize,
+        offset_samples: i32,
+        leadout: i32,
+        buf: &mut [i16],
+    ) -> Result<(), CDRomError> {
+        let samples_per_frame = constants::CD_SAMPLES_PER_FRAME as i64;
+        let frame_len = constants::CD_FRAMESIZE_RAW as usize / 2;
+
+        let start_sample = lba as i64 * samples_per_frame + offset_samples as i64;
+        let first_frame = start_sample.div_euclid(samples_per_frame);
+        let skip = start_sample.rem_euclid(samples_per_frame) as usize;
+
+        // An unaligned window straddles one more frame than was asked for
+        let read_frames = frames + if skip > 0 { 1 } else { 0 };
+        let mut window = vec![0i16; read_frames * frame_len];
+
+        let readable_end = (first_frame + read_frames as i64).min(leadout as i64);
+
+        let readable_start = first_frame.max(0);
+        if readable_start < readable_end {
+            let i = (readable_start - first_frame) as usize * frame_len;
+            let n = (readable_end - readable_start) as usize;
+
+            self.read_audio_range(Addr::Lba(readable_start as i32), n, &mut window[i..i + n * frame_len])?;
+        }
+
+        let start = skip * 2;
+        buf[..frames * frame_len].copy_from_slice(&window[start..start + frames * frame_len]);
+
+        Ok(())
+    }
+
+    /// Read a single raw 2352 byte frame from the CD into a preallocated buffer.
+    ///
+    /// Read failures are returned rather than panicking, so callers iterating
+    /// over a disc can retry or skip the failing sector. Data sectors can be checked
+    /// for corruption the drive didn't report with [`sector::verify_mode1`].
+    pub fn read_raw_into(
+        &mut self,
+        address: Addr,
+        buf: &mut [u8]
+    ) -> Result<(), CDRomError> {
+        self.read_sector(address, buf, constants::CD_FRAMESIZE_RAW as usize, Operation::ReadRaw)
+    }
+
+    /// Read the 2048 bytes of user data in a single Mode 1 sector, as used by data CDs.
+    pub fn read_mode1(
+        &mut self,
+        address: Addr,
+        buf: &mut [u8]
+    ) -> Result<(), CDRomError> {
+        self.read_sector(address, buf, constants::CD_FRAMESIZE as usize, Operation::ReadMode1)
+    }
+
+    /// Read the 2048 bytes of error corrected user data in a single sector, whatever mode
+    /// it is in.
+    ///
+    /// This is a legacy path mostly found on old IDE drives, and most drivers return
+    /// [`CDRomError::Unsupported`]. Use [`CDRom::read_mode1`] or [`CDRom::read_cd`] instead
+    /// where possible.
+    pub fn read_cooked(
+        &mut self,
+        address: Addr,
+        buf: &mut [u8]
+    ) -> Result<(), CDRomError> {
+        match self.read_sector(address, buf, constants::CD_FRAMESIZE as usize, Operation::ReadCooked) {
+            Err(CDRomError::Errno(Errno::ENOTTY | Errno::EINVAL)) => Err(CDRomError::Unsupported),
+            r => r,
+        }
+    }
+
+    /// Read the 2336 bytes following the sync pattern and header of a single Mode 2 sector,
+    /// as used by CD-ROM/XA discs and Video CDs.
+    ///
+    /// Compared to [`CDRom::read_raw_into`], the 12 byte sync pattern and 4 byte header are
+    /// left out. Compared to [`CDRom::read_mode1`], the subheader and error correction data
+    /// are kept, since their layout depends on whether the sector is Form 1 or Form 2.
+    pub fn read_mode2(
+        &mut self,
+        address: Addr,
+        buf: &mut [u8]
+    ) -> Result<(), CDRomError> {
+        self.read_sector(address, buf, constants::CD_FRAMESIZE_RAW0 as usize, Operation::ReadMode2)
+    }
+
+    /// Read a single raw 2352 byte frame followed by its 294 bytes of deinterleaved
+    /// subchannel data, for 2646 bytes in total.
+    ///
+    /// The subchannel data includes the R-W channels that carry CD+G graphics. Only a
+    /// few drivers implement this ioctl; others return [`CDRomError::Unsupported`].
+    pub fn read_raw_sub(
+        &mut self,
+        address: Addr,
+        buf: &mut [u8]
+    ) -> Result<(), CDRomError> {
+        self.read_sector(address, buf, constants::CD_FRAMESIZE_RAWER as usize, Operation::ReadAll)
+    }
+
+    /// Read a single sector with one of the ioctls that take the MSF address at the
+    /// start of the buffer, and overwrite it with `size` bytes of sector data.
+    fn read_sector(
+        &mut self,
+        address: Addr,
+        buf: &mut [u8],
+        size: usize,
+        op: Operation,
+    ) -> Result<(), CDRomError> {
+        let address = match address {
+            Addr::Lba(a) => Msf::from_lba(a),
+            Addr::Msf(msf) => msf,
+        };
+
+        if address.invalid() {
+            return Err(CDRomError::InvalidAddress)
+        }
+
+        if buf.len() < size {
+            return Err(CDRomError::InvalidBufferSize(size, buf.len()))
+        }
+
+        buf[0] = address.minute;
+        buf[1] = address.second;
+        buf[2] = address.frame;
+
+        match unsafe {
+            self.ioctl(op, addr_of_mut!(buf[..size]))
+        } {
+            Ok(_) => Ok(()),
+            Err(e) => Err(errno_to_error(e).at(Addr::Msf(address))),
+        }
+    }
+
+    /// Get the vendor, product, and firmware revision of the drive.
+    pub fn inquiry(&mut self) -> Result<DriveInfo, CDRomError> {
+        let mut buffer = [0u8; 36];
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::GetInquiry as u8;
+        cdb[4] = buffer.len() as u8;
+
+        self.packet_command(cdb, &mut buffer, DataDirection::Read)?;
+
+        let field = |range: std::ops::Range<usize>| {
+            String::from_utf8_lossy(&buffer[range]).trim().to_string()
+        };
+
+        Ok(DriveInfo {
+            vendor: field(8..16),
+            product: field(16..32),
+            revision: field(32..36),
+        })
+    }
+
+    /// Get the recording state of the disc, such as whether it is finalized and how
+    /// many sessions it has.
+    ///
+    /// Unlike [`CDRom::disc_type`], this describes how the disc was written rather than
+    /// what it contains.
+    pub fn disc_information(&mut self) -> Result<DiscInformation, CDRomError> {
+        let mut buffer = [0u8; 34];
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::ReadDiscInfo as u8;
+        cdb[7..9].copy_from_slice(&(buffer.len() as u16).to_be_bytes());
+
+        self.packet_command(cdb, &mut buffer, DataDirection::Read)?;
+
+        Ok(DiscInformation {
+            state: DiscState::from_u8(buffer[2] & 0x03).unwrap(),
+            last_session_state: SessionState::from_u8((buffer[2] >> 2) & 0x03).unwrap(),
+            erasable: buffer[2] & 0x10 != 0,
+            sessions: u16::from_be_bytes([buffer[9], buffer[4]]),
+            first_track: buffer[3] as u16,
+            last_track: u16::from_be_bytes([buffer[10], buffer[6]]),
+        })
+    }
+
+    /// Get the recording state of a track, such as its size and whether it is complete.
+    ///
+    /// Returns [`CDRomError::InvalidAddress`] if the track does not exist.
+    pub fn track_information(&mut self, track: u8) -> Result<TrackInformation, CDRomError> {
+        if track == 0 {
+            return Err(CDRomError::InvalidAddress)
+        }
+
+        let mut buffer = [0u8; 36];
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::ReadTrackRzoneInfo as u8;
+        // Address by track number
+        cdb[1] = 0x01;
+        cdb[5] = track;
+        cdb[7..9].copy_from_slice(&(buffer.len() as u16).to_be_bytes());
+
+        match self.packet_command(cdb, &mut buffer, DataDirection::Read) {
+            Ok(_) => (),
+            // ILLEGAL REQUEST, INVALID FIELD IN CDB
+            Err(CDRomError::Sense { key: 0x05, asc: 0x24, .. }) => return Err(CDRomError::InvalidAddress),
+            Err(e) => return Err(e),
+        }
+
+        let field = |i: usize| u32::from_be_bytes([buffer[i], buffer[i + 1], buffer[i + 2], buffer[i + 3]]);
+
+        Ok(TrackInformation {
+            number: u16::from_be_bytes([buffer[32], buffer[2]]),
+            session: u16::from_be_bytes([buffer[33], buffer[3]]),
+            start: Addr::Lba(field(8) as i32),
+            size: field(24),
+            free_blocks: field(16),
+            blank: buffer[6] & 0x40 != 0,
+            reserved: buffer[6] & 0x80 != 0,
+            damaged: buffer[5] & 0x20 != 0,
+            track_mode: buffer[5] & 0x0F,
+            data_mode: buffer[6] & 0x0F,
+        })
+    }
+
+    /// Read the Absolute Time In Pregroove of a recordable disc, which identifies its
+    /// manufacturer and capacity.
+    ///
+    /// Pressed discs have no ATIP, so this returns [`CDRomError::NotRecordable`] for them.
+    pub fn atip(&mut self) -> Result<Atip, CDRomError> {
+        let data = match self.read_toc_raw(TocFormat::Atip) {
+            Ok(d) => d,
+            Err(CDRomError::ReadError { .. } | CDRomError::Errno(Errno::EIO)) => return Err(CDRomError::NotRecordable),
+            Err(CDRomError::Sense { key: 0x05, .. }) => return Err(CDRomError::NotRecordable),
+            Err(e) => return Err(e),
+        };
+
+        let Some(descriptor) = data.get(4..16) else {
+            return Err(CDRomError::NotRecordable)
+        };
+
+        let leadin = Msf { minute: descriptor[4], second: descriptor[5], frame: descriptor[6] };
+
+        Ok(Atip {
+            manufacturer_id: format!("{:02}:{:02}:{:02}", leadin.minute, leadin.second, leadin.frame),
+            leadin_start: leadin,
+            capacity: Msf { minute: descriptor[8], second: descriptor[9], frame: descriptor[10] },
+            is_rw: descriptor[2] & 0x40 != 0,
+        })
+    }
+
+    /// Make the drive commit its cache, using the SYNCHRONIZE CACHE command.
+    ///
+    /// The command is only required to write out cached writes. Some drives also drop the
+    /// sectors held in their read cache, so later reads come from the disc again, but many
+    /// don't, so this can't be relied on by itself to get fresh data when re-reading.
+    pub fn flush_cache(&mut self) -> Result<(), CDRomError> {
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::FlushCache as u8;
+
+        self.packet_command(cdb, &mut [], DataDirection::None)
+    }
+
+    /// Get the features the drive supports, using the GET CONFIGURATION command.
+    ///
+    /// The first feature is normally the Profile List, and [`Feature::profiles`] shows
+    /// which kind of media is loaded more reliably than [`CDRom::disc_type`].
+    pub fn get_configuration(&mut self) -> Result<Vec<Feature>, CDRomError> {
+        const HEADER_SIZE: usize = 8;
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::GetConfiguration as u8;
+        let buffer = self.read_sized_response(cdb, HEADER_SIZE, 4)?;
+
+        let mut features = Vec::new();
+        let mut descriptors = &buffer[HEADER_SIZE..];
+        while let [code_high, code_low, flags, additional_length, rest @ ..] = descriptors {
+            let additional_length = *additional_length as usize;
+            // The last descriptor may be cut off if the response was too big to read whole
+            let Some(data) = rest.get(..additional_length) else {
+                break
+            };
+
+            features.push(Feature {
+                code: u16::from_be_bytes([*code_high, *code_low]),
+                version: (flags >> 2) & 0x0F,
+                persistent: flags & 0x02 != 0,
+                current: flags & 0x01 != 0,
+                data: data.to_vec(),
+            });
+
+            descriptors = &rest[additional_length..];
+        }
+
+        Ok(features)
+    }
+
+    /// Get the read performance of the disc, using the GET PERFORMANCE command.
+    ///
+    /// The nominal descriptors come first, describing how the read speed changes across
+    /// the disc, which is usually faster towards the outer edge. They are followed by the
+    /// exceptions, places where reading is expected to stall, if the drive reports any.
+    pub fn get_performance(&mut self) -> Result<Vec<PerfDescriptor>, CDRomError> {
+        let nominal = self.performance_data(PERFORMANCE_NOMINAL, 16)?;
+        let mut descriptors: Vec<PerfDescriptor> = nominal
+            .chunks_exact(16)
+            .map(|d| PerfDescriptor::Nominal {
+                start_lba: u32::from_be_bytes([d[0], d[1], d[2], d[3]]),
+                start_kbps: u32::from_be_bytes([d[4], d[5], d[6], d[7]]),
+                end_lba: u32::from_be_bytes([d[8], d[9], d[10], d[11]]),
+                end_kbps: u32::from_be_bytes([d[12], d[13], d[14], d[15]]),
+            })
+            .collect();
+
+        // Not every drive can list exceptions, which doesn't make the nominal data wrong
+        let exceptions = match self.performance_data(PERFORMANCE_EXCEPTIONS, 6) {
+            Ok(e) => e,
+            Err(CDRomError::Sense { key: 0x05, .. }) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        descriptors.extend(exceptions.chunks_exact(6).map(|d| PerfDescriptor::Exception {
+            lba: u32::from_be_bytes([d[0], d[1], d[2], d[3]]),
+            delay: u16::from_be_bytes([d[4], d[5]]),
+        }));
+
+        Ok(descriptors)
+    }
+
+    /// Read the descriptors of the given kind of read performance data, without the header.
+    fn performance_data(&mut self, except: u8, descriptor_size: usize) -> Result<Vec<u8>, CDRomError> {
+        const HEADER_SIZE: usize = 8;
+        const MAX_DESCRIPTORS: u16 = 256;
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::GetPerformance as u8;
+        // Read performance with the tolerance set to 10%
+        cdb[1] = 0x10 | except;
+        cdb[8..10].copy_from_slice(&MAX_DESCRIPTORS.to_be_bytes());
+
+        let mut buffer = vec![0u8; HEADER_SIZE + MAX_DESCRIPTORS as usize * descriptor_size];
+        self.packet_command(cdb, &mut buffer, DataDirection::Read)?;
+
+        // The length field does not include itself
+        let length = (u32::from_be_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as usize + 4)
+            .clamp(HEADER_SIZE, buffer.len());
+
+        Ok(buffer[HEADER_SIZE..length].to_vec())
+    }
+
+    /// Get the state of the drive mechanism and changer, using the MECHANISM STATUS command.
+    ///
+    /// This tells whether the drive is idle, playing or scanning more directly than the
+    /// audio status of [`CDRom::subchannel`], and which slot of a changer is in use.
+    pub fn mechanism_status(&mut self) -> Result<MechanismStatus, CDRomError> {
+        let mut buffer = [0u8; 8];
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::MechanismStatus as u8;
+        cdb[8..10].copy_from_slice(&(buffer.len() as u16).to_be_bytes());
+        self.packet_command(cdb, &mut buffer, DataDirection::Read)?;
+
+        Ok(MechanismStatus {
+            fault: buffer[0] & 0x80 != 0,
+            changer_state: ChangerState::from_u8((buffer[0] >> 5) & 0x03).unwrap(),
+            mechanism_state: MechanismState::from_u8(buffer[1] >> 5),
+            door_open: buffer[1] & 0x10 != 0,
+            current_slot: ((buffer[1] & 0x07) << 5) | (buffer[0] & 0x1F),
+            slots: buffer[5],
+        })
+    }
+
+    /// Poll the drive for the next event, using the GET EVENT STATUS NOTIFICATION command.
+    ///
+    /// Media insertion and removal, tray and power state changes are all reported by a
+    /// single query, so this can be polled far less often than [`CDRom::status`]. Each
+    /// call consumes the event it returns.
+    pub fn poll_event(&mut self) -> Result<DriveEvent, CDRomError> {
+        let mut buffer = [0u8; 8];
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::GetEventStatus as u8;
+        // Polled operation, as asynchronous notification isn't supported through the ioctl
+        cdb[1] = 0x01;
+        // Operational change, power management, external request, media, and device busy classes
+        cdb[4] = 0x5E;
+        cdb[7..9].copy_from_slice(&(buffer.len() as u16).to_be_bytes());
+
+        self.packet_command(cdb, &mut buffer, DataDirection::Read)?;
+
+        // No Event Available
+        if buffer[2] & 0x80 != 0 {
+            return Ok(DriveEvent::NoEvent)
+        }
+
+        let code = buffer[4] & 0x0F;
+        let event = match buffer[2] & 0x07 {
+            0x01 => DriveEvent::OperationalChange(code),
+            0x02 => DriveEvent::PowerManagement(code),
+            0x03 => DriveEvent::ExternalRequest(code),
+            0x04 => DriveEvent::Media {
+                event: MediaEvent::from(code),
+                tray_open: buffer[5] & 0x01 != 0,
+                media_present: buffer[5] & 0x02 != 0,
+            },
+            0x06 => DriveEvent::DeviceBusy(code),
+            _ => return Err(CDRomError::ProtocolViolation),
+        };
+
+        Ok(event)
+    }
+
+    /// Check whether the drive is ready to read, using the TEST UNIT READY command.
+    ///
+    /// Returns `false` while the drive is still becoming ready, such as when the disc is
+    /// spinning up, and [`CDRomError::NoDisc`] if there is no disc at all.
+    pub fn test_unit_ready(&mut self) -> Result<bool, CDRomError> {
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::TestUnitReady as u8;
+
+        match self.packet_command(cdb, &mut [], DataDirection::None) {
+            Ok(_) => Ok(true),
+            // NOT READY, LOGICAL UNIT IS IN PROCESS OF BECOMING READY
+            Err(CDRomError::Sense { key: 0x02, asc: 0x04, ascq: 0x01 }) => Ok(false),
+            // UNIT ATTENTION, the disc was changed or the drive was reset
+            Err(CDRomError::Sense { key: 0x06, .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Load the disc into the drive, closing the tray if there is one.
+    pub fn load(&mut self) -> Result<(), CDRomError> {
+        self.start_stop_unit(true, true)
+    }
+
+    /// Unload the disc from the drive, opening the tray if there is one.
+    pub fn unload(&mut self) -> Result<(), CDRomError> {
+        self.start_stop_unit(false, true)
+    }
+
+    /// Spin up the disc without reading from it.
+    pub fn spin_up(&mut self) -> Result<(), CDRomError> {
+        self.start_stop_unit(true, false)
+    }
+
+    /// Spin down the disc, leaving it in the drive.
+    pub fn spin_down(&mut self) -> Result<(), CDRomError> {
+        self.start_stop_unit(false, false)
+    }
+
+    /// Issue a START STOP UNIT command, which waits until the operation completes.
+    ///
+    /// With `load_eject` set, the disc is loaded when starting and unloaded when stopping.
+    fn start_stop_unit(&mut self, start: bool, load_eject: bool) -> Result<(), CDRomError> {
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::StartStopUnit as u8;
+        // Power condition 0 leaves the power state alone and uses the START and LOEJ bits
+        cdb[4] = (load_eject as u8) << 1 | start as u8;
+
+        self.packet_command(cdb, &mut [], DataDirection::None)
+    }
+
+    /// Read the current values of a mode page with the MMC MODE SENSE (10) command.
+    ///
+    /// The page is returned without the mode parameter header, starting from the page
+    /// code, so it can be changed and passed back to [`CDRom::mode_select`]. Page `0x2A`
+    /// ([`constants::MODE_PAGE_CAPABILITIES`]) holds the capabilities of the drive, such
+    /// as its maximum speed and buffer size, and page `0x0E` controls the audio output.
+    pub fn mode_sense(&mut self, page: u8) -> Result<Vec<u8>, CDRomError> {
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::ModeSense10 as u8;
+        // No block descriptors
+        cdb[1] = 0x08;
+        cdb[2] = page & 0x3F;
+        let buffer = self.read_sized_response(cdb, MODE_HEADER_SIZE, 2)?;
+
+        // Skip any block descriptors the drive returned anyway
+        let start = MODE_HEADER_SIZE + u16::from_be_bytes([buffer[6], buffer[7]]) as usize;
+        let data = buffer.get(start..).ok_or(CDRomError::ProtocolViolation)?;
+        if data.len() < 2 || data[0] & 0x3F != page & 0x3F {
+            return Err(CDRomError::ProtocolViolation)
+        }
+
+        let end = (data[1] as usize + 2).min(data.len());
+        Ok(data[..end].to_vec())
+    }
+
+    /// Change the values of a mode page with the MMC MODE SELECT (10) command.
+    ///
+    /// `page` starts from the page code, as returned by [`CDRom::mode_sense`]. Only the
+    /// fields the drive reports as changeable can be changed, and the drive returns
+    /// [`CDRomError::Sense`] with an invalid field error for any others.
+    pub fn mode_select(&mut self, page: &[u8]) -> Result<(), CDRomError> {
+        if page.len() < 2 {
+            return Err(CDRomError::InvalidBufferSize(2, page.len()))
+        }
+
+        let mut buffer = vec![0u8; MODE_HEADER_SIZE + page.len()];
+        buffer[MODE_HEADER_SIZE..].copy_from_slice(page);
+        // The parameters saveable bit is reserved for MODE SELECT
+        buffer[MODE_HEADER_SIZE] &= 0x7F;
+
+        let mut cdb = [0u8; CDROM_PACKET_SIZE];
+        cdb[0] = GenericPacketCommand::ModeSelect10 as u8;
+        // Page format
+        cdb[1] = 0x10;
+        cdb[7..9].copy_from_slice(&(buffer.len() as u16).to_be_bytes());
+
+        self.packet_command(cdb, &mut buffer, DataDirection::Write)
+    }
+
+    /// Send a packet command to the drive.
+    ///
+    /// If the command fails and the drive returns sense data, it is converted with
+    /// [`RequestSense::as_error`]. When `cmd.sense` is set, the sense data is also written
+    /// there.
+    ///
+    /// # Safety
+    /// `cmd.buffer` must point to `cmd.buflen` bytes of memory that stay valid for the
+    /// whole call, and that can be written to if the command reads data from the drive.
+    /// [`GenericCommand::new`] sets these from a slice, which must not be dropped before
+    /// this is called. If `cmd.sense` is not null, it must point to a valid, writable
+    /// [`RequestSense`].
+    pub unsafe fn send_packet(&mut self, cmd: &mut GenericCommand) -> Result<(), CDRomError> {
+        let mut local_sense = RequestSense::default();
+        let user_sense = cmd.sense;
+        if user_sense.is_null() {
+            cmd.sense = addr_of_mut!(local_sense);
+        }
+
+        let result = self.ioctl(Operation::SendPacket, cmd);
+
+        let sense = *cmd.sense;
+        cmd.sense = user_sense;
+
+        if result.is_ok() && cmd.stat == 0 {
+            return Ok(())
+        }
+
+        if let Some(e) = sense.as_error() {
+            return Err(e)
+        }
+
+        match result {
+            Ok(_) => Err(CDRomError::Errno(Errno::EIO)),
+            Err(e) => Err(errno_to_error(e)),
+        }
+    }
+
+    /// Issue a packet command whose response starts with a big-endian length field of
+    /// `length_size` bytes, and return the whole response, including the header of
+    /// `header_size` bytes.
+    ///
+    /// The header is read first to find out how much data there is, then the command is
+    /// repeated with an allocation length in bytes 7 and 8 of `cdb` big enough for all of it.
+    fn read_sized_response(
+        &mut self,
+        mut cdb: [u8; CDROM_PACKET_SIZE],
+        header_size: usize,
+        length_size: usize,
+    ) -> Result<Vec<u8>, CDRomError> {
+        let mut header = vec![0u8; header_size];
+        cdb[7..9].copy_from_slice(&(header_size as u16).to_be_bytes());
+        self.packet_command(cdb, &mut header, DataDirection::Read)?;
+
+        // The length field does not include itself
+        let length = header[..length_size].iter().fold(0usize, |n, b| n << 8 | *b as usize) + length_size;
+        let length = length.min(u16::MAX as usize);
+        if length <= header_size {
+            return Ok(header)
+        }
+
+        let mut buffer = vec![0u8; length];
+        cdb[7..9].copy_from_slice(&(length as u16).to_be_bytes());
+        self.packet_command(cdb, &mut buffer, DataDirection::Read)?;
+
+        Ok(buffer)
+    }
+
+    /// Issue a packet command to the drive, transferring data through `buffer`.
+    fn packet_command(
+        &mut self,
+        cdb: [u8; CDROM_PACKET_SIZE],
+        buffer: &mut [u8],
+        direction: DataDirection,
+    ) -> Result<(), CDRomError> {
+        let mut cmd = GenericCommand::new(cdb, buffer, direction);
+        // The buffer is borrowed for as long as the command is used, and there is no
+        // sense pointer
+        unsafe { self.send_packet(&mut cmd) }
+    }
+
+    /// Issue an ioctl that takes a pointer to `arg`.
+    unsafe fn ioctl<T: ?Sized>(&mut self, op: Operation, arg: *const T) -> nix::Result<i32> {
+        self.backend.ioctl(op_to_ioctl(op), arg.cast::<u8>() as libc::c_ulong)
+    }
+
+    /// Issue an ioctl that takes an integer argument.
+    unsafe fn ioctl_int(&mut self, op: Operation, arg: i32) -> nix::Result<i32> {
+        self.backend.ioctl(op_to_ioctl(op), arg as libc::c_ulong)
+    }
+
+    /// Issue an ioctl that takes no argument.
+    unsafe fn ioctl_none(&mut self, op: Operation) -> nix::Result<i32> {
+        self.backend.ioctl(op_to_ioctl(op), 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::MockBackend;
+
+    #[test]
+    fn set_lock_succeeds_when_the_ioctl_returns_zero() {
+        let mut drive = CDRom::with_backend(MockBackend::default());
+
+        assert!(drive.set_lock(true).is_ok());
+        assert!(drive.set_lock(false).is_ok());
+    }
+
+    #[test]
+    fn set_lock_falls_back_to_prevent_removal() {
+        let mut drive = CDRom::with_backend(MockBackend {
+            can_lock: false,
+            ..Default::default()
+        });
+
+        // Only PREVENT ALLOW MEDIUM REMOVAL can succeed here
+        assert!(drive.set_lock(true).is_ok());
+        assert!(drive.set_lock(false).is_ok());
+    }
+
+    #[test]
+    fn read_audio_into_rejects_invalid_msf() {
+        let mut drive = CDRom::with_backend(MockBackend::default());
+        let mut buf = vec![0i16; constants::CD_FRAMESIZE_RAW as usize / 2];
+
+        for msf in [Msf { minute: 0, second: 1, frame: 74 }, Msf { minute: 1, second: 60, frame: 0 }] {
+            assert!(matches!(drive.read_audio_into(Addr::Msf(msf), 1, &mut buf), Err(CDRomError::InvalidAddress)));
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Calculation of MusicBrainz Disc IDs.
+//!
+//! See <https://musicbrainz.org/doc/Disc_ID_Calculation> for the algorithm.
+
+use sha1_smol::Sha1;
+
+use crate::constants;
+
+/// Base64 alphabet used by MusicBrainz, which replaces `+`, `/` and `=`
+/// with `.`, `_` and `-` so the ID is URL safe.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789._";
+const PADDING: u8 = b'-';
+
+/// Compute the Disc ID from the first and last track numbers, the LBA of the
+/// lead-out, and the LBA of each track starting from `first_track`.
+pub fn disc_id(first_track: u8, last_track: u8, leadout: i32, offsets: &[i32]) -> String {
+    let mut hasher = Sha1::new();
+
+    hasher.update(format!("{:02X}", first_track).as_bytes());
+    hasher.update(format!("{:02X}", last_track).as_bytes());
+
+    // The lead-out goes in slot 0 and track N in slot N, with zeros for the tracks
+    // the disc doesn't have. Offsets are in sectors from the start of the disc,
+    // including the 2 second pregap.
+    hasher.update(format!("{:08X}", leadout + constants::CD_MSF_OFFSET).as_bytes());
+    for track in 1..100 {
+        let offset = (track as usize)
+            .checked_sub(first_track as usize)
+            .and_then(|i| offsets.get(i))
+            .map(|o| o + constants::CD_MSF_OFFSET)
+            .unwrap_or(0);
+
+        hasher.update(format!("{:08X}", offset).as_bytes());
+    }
+
+    encode(&hasher.digest().bytes())
+}
+
+/// Encode bytes in the MusicBrainz variant of base64.
+fn encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(ALPHABET[((n >> (18 - i * 6)) & 0x3F) as usize] as char);
+            } else {
+                output.push(PADDING as char);
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_libdiscid_test_disc() {
+        // The TOC from libdiscid's tests, less the 150 sector pregap
+        let offsets = [0, 18751, 39588, 59407, 79002, 99976, 124683, 147128, 166186, 182410];
+
+        assert_eq!(disc_id(1, 10, 206385, &offsets), "Wn8eRBtfLDfM0qjYPdxrz.Zjs_U-");
+    }
+
+    #[test]
+    fn tracks_go_in_the_slot_of_their_number() {
+        // A disc starting at track 3 leaves slots 1 and 2 empty
+        let mut toc = String::from("0304");
+        toc += "00001B58";
+        toc += "0000000000000000";
+        toc += "000000960000109A";
+        toc += &"0".repeat(8 * 95);
+
+        let mut hasher = Sha1::new();
+        hasher.update(toc.as_bytes());
+
+        assert_eq!(disc_id(3, 4, 6850, &[0, 4100]), encode(&hasher.digest().bytes()));
+    }
+}
@@ -1,4 +1,6 @@
-use std::{fmt::{write, Debug}, os::raw::c_void};
+use std::{fmt::{write, Debug}, mem, os::raw::c_void, ptr};
+
+use crate::{structures::Addr, CDRomError};
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
@@ -14,6 +16,27 @@ pub struct GenericCommand {
     pub u: U
 }
 
+impl GenericCommand {
+    /// Create a command for the given CDB, transferring data through `buffer`
+    /// in the given direction.
+    ///
+    /// The buffer must outlive the command.
+    pub fn new(cdb: [u8; CDROM_PACKET_SIZE], buffer: &mut [u8], data_direction: DataDirection) -> Self {
+        Self {
+            cdb,
+            buffer: buffer.as_mut_ptr(),
+            buflen: buffer.len() as u32,
+            stat: 0,
+            sense: ptr::null_mut(),
+            data_direction,
+            quiet: 0,
+            // Zero lets the driver use its default timeout
+            timeout: 0,
+            u: U { unused: ptr::null() },
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
 pub struct RequestSense {
@@ -30,6 +53,46 @@ pub struct RequestSense {
     pub asb: [u8; 46]
 }
 
+impl Default for RequestSense {
+    fn default() -> Self {
+        unsafe { mem::zeroed() }
+    }
+}
+
+impl RequestSense {
+    /// Convert the sense data into an error, if it reports one.
+    ///
+    /// Common combinations of sense key, ASC, and ASCQ are turned into the
+    /// matching [`CDRomError`] variant, and anything else is returned as
+    /// [`CDRomError::Sense`].
+    pub fn as_error(&self) -> Option<CDRomError> {
+        let key = self.reserved.sense_key();
+        if key == 0 {
+            return None
+        }
+
+        let error = match (key, self.asc, self.ascq) {
+            // NOT READY, MEDIUM NOT PRESENT
+            (0x02, 0x3A, _) => CDRomError::NoDisc,
+            // MEDIUM ERROR, UNRECOVERED READ ERROR
+            (0x03, 0x11, _) => CDRomError::ReadError {
+                // The information field holds the failing LBA when it is valid
+                address: (self.valid_error.valid() != 0)
+                    .then(|| Addr::Lba(i32::from_be_bytes(self.information))),
+            },
+            // ILLEGAL REQUEST, INVALID COMMAND OPERATION CODE
+            (0x05, 0x20, _) => CDRomError::Unsupported,
+            // ILLEGAL REQUEST, LOGICAL BLOCK ADDRESS OUT OF RANGE
+            (0x05, 0x21, _) => CDRomError::InvalidAddress,
+            // ILLEGAL REQUEST, MEDIUM REMOVAL PREVENTED
+            (0x05, 0x53, 0x02) => CDRomError::DoorLocked,
+            (key, asc, ascq) => CDRomError::Sense { key, asc, ascq },
+        };
+
+        Some(error)
+    }
+}
+
 #[cfg(target_endian = "big")]
 #[bitfield_struct::bitfield(u8)]
 pub struct ValidError {
@@ -88,6 +151,10 @@ impl Debug for U {
 }
 
 pub const CDROM_PACKET_SIZE: usize = 12;
+
+/// Size of the header before the mode pages in MODE SENSE (10) and MODE SELECT (10) data
+pub const MODE_HEADER_SIZE: usize = 8;
+
 /// The generic packet command opcodes for CD/DVD Logical Units,
 /// From Table 57 of the SFF8090 Ver. 3 (Mt. Fuji) draft standard.
 #[derive(FromPrimitive, ToPrimitive)]
@@ -0,0 +1,140 @@
+//! Jitter correction for drives that don't read audio accurately.
+//!
+//! Cheaper drives don't always start reading audio at exactly the sector that
+//! was asked for, but a few samples before or after it. Reading each chunk so
+//! that it overlaps the end of the previous one lets the overlap be matched up,
+//! and the chunks stitched together into a continuous stream.
+
+use crate::{constants, structures::Addr, CDRom, CDRomError};
+
+/// Number of `i16` values in a frame of audio, two for each stereo sample
+const FRAME_VALUES: usize = constants::CD_FRAMESIZE_RAW as usize / 2;
+
+/// Number of frames read at a time, not including the overlap
+const CHUNK_FRAMES: usize = 25;
+
+/// Number of frames each read overlaps the previous one by
+const OVERLAP_FRAMES: usize = 2;
+
+/// Values at the end of the stream that are compared against each new read
+const MATCH_VALUES: usize = FRAME_VALUES;
+
+/// Furthest a read can be shifted by jitter, in stereo samples, and still be matched
+const MAX_JITTER: isize = (FRAME_VALUES / 2) as isize;
+
+/// Times a chunk is read again when it doesn't match up with the previous one
+const MAX_REREADS: u32 = 5;
+
+/// Times a single read is retried when the drive reports an error
+const READ_ATTEMPTS: u32 = 3;
+
+/// Reads audio in overlapping chunks, correcting for jitter between reads.
+pub struct ParanoiaReader<'a> {
+    drive: &'a mut CDRom,
+    /// Whether re-reads bypass the drive's cache
+    uncached_rereads: bool,
+}
+
+impl<'a> ParanoiaReader<'a> {
+    pub fn new(drive: &'a mut CDRom) -> Self {
+        Self { drive, uncached_rereads: false }
+    }
+
+    /// Set whether chunks that are read again are read with
+    /// [`CDRom::read_audio_uncached`], so the drive can't return the same cached data.
+    ///
+    /// This is off by default, since every re-read then needs a seek, which slows down
+    /// ripping considerably. On scratched discs it makes re-reads far more likely to
+    /// recover the right audio.
+    pub fn set_uncached_rereads(&mut self, enabled: bool) {
+        self.uncached_rereads = enabled;
+    }
+
+    /// Read `frames` frames of audio starting at `start`, with the jitter between
+    /// reads corrected.
+    ///
+    /// The first chunk read is taken as the reference, so the stream as a whole may
+    /// still be offset in the same way as [`CDRom::read_audio`].
+    pub fn read_verified(&mut self, start: Addr, frames: usize) -> Result<Vec<i16>, CDRomError> {
+        let start = start.into_lba();
+        let total = frames * FRAME_VALUES;
+
+        let mut stream = Vec::with_capacity(total + CHUNK_FRAMES * FRAME_VALUES);
+        if frames == 0 {
+            return Ok(stream)
+        }
+
+        let first = frames.min(CHUNK_FRAMES + OVERLAP_FRAMES);
+        stream.extend(self.read_chunk(start, first, false)?);
+
+        while stream.len() < total {
+            // The sector the end of the stream would be at, if there were no jitter
+            let end = start + (stream.len() / FRAME_VALUES) as i32;
+            let end_offset = stream.len() % FRAME_VALUES;
+
+            // Can't overlap further back than the start of the stream
+            let overlap = OVERLAP_FRAMES.min(stream.len() / FRAME_VALUES);
+            let remaining = (total - stream.len()).div_ceil(FRAME_VALUES);
+            let count = overlap + remaining.min(CHUNK_FRAMES);
+            let read_start = end - overlap as i32;
+
+            let mut attempt = 0;
+            let chunk = loop {
+                let chunk = self.read_chunk(read_start, count, attempt > 0)?;
+
+                // Where the end of the stream is in the chunk, if there were no jitter
+                let expected = overlap * FRAME_VALUES + end_offset;
+                if let Some(position) = find_overlap(&stream, &chunk, expected) {
+                    break chunk[position..].to_vec()
+                }
+
+                attempt += 1;
+                if attempt >= MAX_REREADS {
+                    // Give up on matching, and assume the read wasn't shifted
+                    break chunk[expected..].to_vec()
+                }
+            };
+
+            stream.extend(chunk);
+        }
+
+        stream.truncate(total);
+        Ok(stream)
+    }
+
+    fn read_chunk(&mut self, lba: i32, frames: usize, reread: bool) -> Result<Vec<i16>, CDRomError> {
+        let mut buffer = vec![0i16; frames * FRAME_VALUES];
+        if reread && self.uncached_rereads {
+            self.drive.read_audio_uncached(Addr::Lba(lba), frames, &mut buffer, READ_ATTEMPTS)?;
+        } else {
+            self.drive.read_audio_retry(Addr::Lba(lba), frames, &mut buffer, READ_ATTEMPTS)?;
+        }
+
+        Ok(buffer)
+    }
+}
+
+/// Find where the end of `stream` is in `chunk`, searching outwards from `expected`.
+///
+/// Returns the index in `chunk` just past the matching values. Matches that leave
+/// nothing after them in `chunk` are skipped, so the stream always grows.
+fn find_overlap(stream: &[i16], chunk: &[i16], expected: usize) -> Option<usize> {
+    let window = MATCH_VALUES.min(stream.len()).min(expected);
+    let tail = &stream[stream.len() - window..];
+
+    // Try the smallest shifts first, so runs of silence match without a shift
+    for shift in (0..=MAX_JITTER).flat_map(|s| [s, -s]) {
+        let Some(end) = expected.checked_add_signed(shift * 2).filter(|e| *e < chunk.len()) else {
+            continue
+        };
+        let Some(candidate) = end.checked_sub(window).and_then(|s| chunk.get(s..end)) else {
+            continue
+        };
+
+        if candidate == tail {
+            return Some(end)
+        }
+    }
+
+    None
+}
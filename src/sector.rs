@@ -0,0 +1,65 @@
+//! Checks on raw 2352 byte data sectors, for catching corruption the drive didn't report.
+
+use crate::constants;
+use crate::structures::{from_bcd, Addr, Msf};
+use crate::CDRomError;
+
+/// The 12 bytes every raw data sector starts with
+pub const SYNC_PATTERN: [u8; 12] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+
+/// The reversed polynomial of the CRC-32 used for the EDC of data sectors,
+/// x^32 + x^31 + x^16 + x^15 + x^4 + x^3 + x + 1
+pub const EDC_POLYNOMIAL: u32 = 0xD801_8001;
+
+const HEADER_END: usize = (constants::CD_SYNC_SIZE + constants::CD_HEAD_SIZE) as usize;
+const EDC_START: usize = HEADER_END + constants::CD_FRAMESIZE as usize;
+const EDC_END: usize = EDC_START + constants::CD_EDC_SIZE as usize;
+
+/// Compute the error detection code of `data`.
+pub fn edc(data: &[u8]) -> u32 {
+    let mut edc: u32 = 0;
+    for byte in data {
+        edc ^= *byte as u32;
+        for _ in 0..8 {
+            edc = if edc & 1 != 0 { (edc >> 1) ^ EDC_POLYNOMIAL } else { edc >> 1 };
+        }
+    }
+
+    edc
+}
+
+/// Check that a raw sector, as read by [`CDRom::read_raw_into`](crate::CDRom::read_raw_into),
+/// is an intact Mode 1 sector.
+///
+/// The sync pattern and header are checked, and the EDC is recomputed over the sync
+/// pattern, header, and user data and compared with the one stored in the sector.
+///
+/// Returns [`CDRomError::InvalidBufferSize`] if `sector` is shorter than 2352 bytes,
+/// [`CDRomError::ProtocolViolation`] if it isn't a Mode 1 sector, and
+/// [`CDRomError::ReadError`] with the address from the header if the EDC doesn't match.
+pub fn verify_mode1(sector: &[u8]) -> Result<(), CDRomError> {
+    let Some(sector) = sector.get(..constants::CD_FRAMESIZE_RAW as usize) else {
+        return Err(CDRomError::InvalidBufferSize(constants::CD_FRAMESIZE_RAW as usize, sector.len()))
+    };
+
+    let header = &sector[constants::CD_SYNC_SIZE as usize..HEADER_END];
+    if sector[..SYNC_PATTERN.len()] != SYNC_PATTERN
+        || header[3] != 0x01
+        || header[..3].iter().any(|b| b & 0x0F > 9 || b >> 4 > 9)
+    {
+        return Err(CDRomError::ProtocolViolation)
+    }
+
+    let stored = u32::from_le_bytes(sector[EDC_START..EDC_END].try_into().unwrap());
+    if edc(&sector[..EDC_START]) != stored {
+        let address = Msf {
+            minute: from_bcd(header[0]),
+            second: from_bcd(header[1]),
+            frame: from_bcd(header[2]),
+        };
+
+        return Err(CDRomError::ReadError { address: Some(Addr::Msf(address)) })
+    }
+
+    Ok(())
+}
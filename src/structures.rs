@@ -1,10 +1,11 @@
-use std::{ffi::c_int, mem};
+use std::{cmp::Ordering, ffi::c_int, fmt, mem, ops::{BitOr, BitOrAssign, Sub}};
 
-use crate::constants::{self, AddressType};
+use crate::constants::{self, AddressType, Capability, DiscState, SessionState};
 
 /// Address in MSF format
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Msf {
     pub minute: u8,
     pub second: u8,
@@ -12,25 +13,94 @@ pub struct Msf {
 }
 
 impl Msf {
+    /// Convert the MSF address to a logical block address.
+    ///
+    /// Times from `90:00:00` onwards are treated as lead-in addresses, and map to
+    /// LBAs below `-150`.
+    ///
+    /// Any MSF converts without overflowing, even a garbled one like `255:255:255`,
+    /// since the largest frame count it can hold is 1,166,880.
     pub fn to_lba(&self) -> i32 {
-        (((self.minute as i32 * constants::CD_SECS) + self.second as i32) * constants::CD_FRAMES + self.frame as i32) - constants::CD_MSF_OFFSET
+        let frames = ((self.minute as i32 * constants::CD_SECS) + self.second as i32) * constants::CD_FRAMES + self.frame as i32;
+
+        if self.minute >= 90 {
+            frames - constants::CD_LEADIN_MSF_OFFSET
+        } else {
+            frames - constants::CD_MSF_OFFSET
+        }
     }
 
+    /// Convert a logical block address to MSF.
+    ///
+    /// Addresses in the pregap of the first track are negative, from `-150` for
+    /// `00:00:00` up to `0` for `00:02:00`. Addresses below that are in the lead-in,
+    /// and map to times from `90:00:00` onwards.
     pub fn from_lba(lba: i32) -> Self {
-        let offset_a = lba + constants::CD_MSF_OFFSET;
+        let offset_a = if lba < -constants::CD_MSF_OFFSET {
+            lba + constants::CD_LEADIN_MSF_OFFSET
+        } else {
+            lba + constants::CD_MSF_OFFSET
+        };
         Msf {
-            minute: ((offset_a / constants::CD_FRAMES) / 60) as u8,
-            second: ((offset_a / constants::CD_FRAMES) % 60) as u8,
-            frame: (offset_a % 75) as u8,
+            minute: (offset_a.div_euclid(constants::CD_FRAMES) / constants::CD_SECS) as u8,
+            second: (offset_a.div_euclid(constants::CD_FRAMES) % constants::CD_SECS) as u8,
+            frame: offset_a.rem_euclid(constants::CD_FRAMES) as u8,
         }
     }
 
+    /// Number of frames from this address to `other`, which is negative if
+    /// `other` comes first.
+    pub fn frames_between(&self, other: &Msf) -> i32 {
+        other.to_lba() - self.to_lba()
+    }
+
+    /// Get the address `n` frames after this one, or before it if `n` is negative.
+    ///
+    /// The result is clamped to the addresses that pass [`Msf::invalid`], from `00:02:00`
+    /// to `89:59:74`, so it never lands in the pregap or the lead-in.
+    pub fn add_frames(&self, n: i32) -> Msf {
+        let max = 90 * constants::CD_SECS * constants::CD_FRAMES - 1 - constants::CD_MSF_OFFSET;
+
+        Msf::from_lba(self.to_lba().saturating_add(n).clamp(0, max))
+    }
+
+    /// Check whether the address can't be read from.
+    ///
+    /// This is the case for addresses in the pregap before `00:02:00`, and for
+    /// seconds or frames that are out of range.
     pub fn invalid(&self) -> bool {
-        if self.minute == 0 && self.second < 2 {
-            true
-        } else {
-            false
-        }
+        (self.minute == 0 && self.second < 2)
+            || self.second as i32 >= constants::CD_SECS
+            || self.frame as i32 >= constants::CD_FRAMES
+    }
+}
+
+impl PartialEq for Msf {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_lba() == other.to_lba()
+    }
+}
+
+impl Eq for Msf {}
+
+impl PartialOrd for Msf {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Msf {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_lba().cmp(&other.to_lba())
+    }
+}
+
+impl Sub for Msf {
+    type Output = i32;
+
+    /// The number of frames between two addresses.
+    fn sub(self, rhs: Self) -> Self::Output {
+        rhs.frames_between(&self)
     }
 }
 
@@ -43,11 +113,34 @@ pub union AddrUnion {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Addr {
     Lba(i32),
     Msf(Msf),
 }
 
+/// Addresses are compared by their position on the disc, so an [`Addr::Lba`]
+/// and an [`Addr::Msf`] pointing at the same sector are equal.
+impl PartialEq for Addr {
+    fn eq(&self, other: &Self) -> bool {
+        self.into_lba() == other.into_lba()
+    }
+}
+
+impl Eq for Addr {}
+
+impl PartialOrd for Addr {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Addr {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.into_lba().cmp(&other.into_lba())
+    }
+}
+
 impl Addr {
     pub fn into_msf(self) -> Msf {
         match self {
@@ -64,6 +157,30 @@ impl Addr {
     }
 }
 
+impl From<Msf> for Addr {
+    fn from(msf: Msf) -> Self {
+        Addr::Msf(msf)
+    }
+}
+
+impl From<i32> for Addr {
+    fn from(lba: i32) -> Self {
+        Addr::Lba(lba)
+    }
+}
+
+impl From<Addr> for Msf {
+    fn from(addr: Addr) -> Self {
+        addr.into_msf()
+    }
+}
+
+impl From<Addr> for i32 {
+    fn from(addr: Addr) -> Self {
+        addr.into_lba()
+    }
+}
+
 /// This struct is used by [`crate::constants::PLAY_MSF`]
 #[repr(C)]
 #[derive(Clone, Copy, Default)]
@@ -88,22 +205,44 @@ pub union RawResult {
     pub buffer: [u8; constants::CD_FRAMESIZE_RAW as usize],
 }
 
+/// This struct is used by [`crate::constants::Operation::PlayBlock`]
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct Blk {
+    /// First logical block to play
+    pub from: u32,
+    /// Number of blocks to play
+    pub len: u16,
+}
+
+/// This struct is used by [`crate::constants::Operation::TimedMediaChange`]
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub(crate) struct _TimedMediaChangeInfo {
+    /// Milliseconds since boot of the last media change known to the caller on the
+    /// way in, and of the last media change known to the kernel on the way out
+    pub last_media_change: i64,
+    pub media_flags: u64,
+}
+
 /// This struct is used by [`crate::constants::PLAY_TRACK_INDEX`]
 #[repr(C)]
-struct TrackIndex {
+#[derive(Clone, Copy, Default)]
+pub struct TrackIndex {
     /// Start track
-    trk0: u8,
+    pub trk0: u8,
     /// Start index
-    ind0: u8,
+    pub ind0: u8,
     /// End track
-    trk1: u8,
+    pub trk1: u8,
     /// End index
-    ind1: u8,
+    pub ind1: u8,
 }
 
 /// This struct is used by [`crate::constants::READ_TOC_HEADER`]
 #[repr(C)]
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TocHeader {
     pub first_track: u8,
     pub last_track: u8,
@@ -135,15 +274,329 @@ impl Default for _TocEntry {
 
 // Actually public version of [`_TocEntry`].
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TocEntry {
     pub track: u8,
     pub adr: u8,
     pub ctrl: u8,
     pub addr: Addr,
+    /// Data mode of the track, as reported by the driver
+    ///
+    /// The Linux drivers don't read the mode from the disc, and set this to `1` for every
+    /// data track and `0` for audio tracks, so it can't tell Mode 1 and Mode 2 apart. Read
+    /// the header of a raw sector with [`CDRom::read_raw_into`](crate::CDRom::read_raw_into)
+    /// to find the real mode.
+    pub datamode: u8,
+}
+
+impl TocEntry {
+    /// Whether the track contains audio.
+    pub fn is_audio(&self) -> bool {
+        !self.is_data()
+    }
+
+    /// Whether the track contains data.
+    pub fn is_data(&self) -> bool {
+        self.ctrl & 0x04 != 0
+    }
+
+    /// Whether the audio was recorded with pre-emphasis, and needs de-emphasis on playback.
+    pub fn has_preemphasis(&self) -> bool {
+        self.is_audio() && self.ctrl & 0x01 != 0
+    }
+
+    /// Whether digital copying of the track is permitted.
+    pub fn copy_permitted(&self) -> bool {
+        self.ctrl & 0x02 != 0
+    }
+
+    /// Whether the track contains four channel audio rather than stereo.
+    pub fn is_four_channel(&self) -> bool {
+        self.is_audio() && self.ctrl & 0x08 != 0
+    }
+}
+
+// This struct is used by the [`crate::constants::Operation::MultiSession`] ioctl
+#[repr(C)]
+pub(crate) struct _MultiSession {
+    pub addr: AddrUnion,
+    pub xa_flag: u8,
+    pub addr_format: u8,
+}
+
+impl Default for _MultiSession {
+    fn default() -> Self {
+        unsafe {
+            Self {
+                addr: mem::zeroed(),
+                xa_flag: 0,
+                addr_format: AddressType::Msf as u8,
+            }
+        }
+    }
+}
+
+/// Multisession information of a disc.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultiSession {
+    /// Start of the last session on the disc
+    pub addr: Addr,
+    /// Whether the disc has more than one session, as on CD-Extra/enhanced CDs
+    pub is_multisession: bool,
+}
+
+/// A track on the disc, as described by the table of contents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Track {
+    /// Track number, starting from 1
+    pub number: u8,
+    /// Address of the start of the track
+    pub start: Addr,
+    /// Length of the track in frames, up to the start of the next track or the lead-out
+    pub length_frames: u32,
+    /// Whether the track contains audio rather than data
+    pub is_audio: bool,
+    /// International Standard Recording Code of the track, if it has one
+    pub isrc: Option<String>,
+}
+
+/// A Media Catalog Number, which is the 13 digit UPC/EAN barcode of the disc.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mcn(String);
+
+impl Mcn {
+    /// Validate a Media Catalog Number.
+    ///
+    /// Returns `None` unless the code is 13 digits with a correct EAN-13 check digit.
+    pub fn new(code: &str) -> Option<Self> {
+        let digits: Vec<u32> = code.chars().map(|c| c.to_digit(10)).collect::<Option<_>>()?;
+        if digits.len() != 13 {
+            return None
+        }
+
+        let sum: u32 = digits[..12]
+            .iter()
+            .enumerate()
+            .map(|(i, d)| if i % 2 == 0 { *d } else { d * 3 })
+            .sum();
+
+        if (10 - sum % 10) % 10 != digits[12] {
+            return None
+        }
+
+        Some(Self(code.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Mcn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Identity of a drive, as reported by the INQUIRY command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriveInfo {
+    pub vendor: String,
+    pub product: String,
+    pub revision: String,
+}
+
+/// Recording state of a disc, as reported by the READ DISC INFORMATION command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiscInformation {
+    pub state: DiscState,
+    pub last_session_state: SessionState,
+    /// The disc can be erased, such as a CD-RW
+    pub erasable: bool,
+    pub sessions: u16,
+    pub first_track: u16,
+    /// Last track of the last session
+    pub last_track: u16,
+}
+
+/// Recording state of a single track, as reported by the READ TRACK INFORMATION command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrackInformation {
+    pub number: u16,
+    pub session: u16,
+    pub start: Addr,
+    /// Length of the track in sectors
+    pub size: u32,
+    /// Sectors that can still be written to the track
+    pub free_blocks: u32,
+    /// Nothing has been written to the track
+    pub blank: bool,
+    /// The track has been reserved, and may not be complete
+    pub reserved: bool,
+    /// The track was left damaged by an interrupted write
+    pub damaged: bool,
+    /// The control nibble of the track, the same as [`TocEntry::ctrl`]
+    pub track_mode: u8,
+    /// `1` for Mode 1 or `2` for Mode 2 data, and `0x0F` when the mode is unknown
+    pub data_mode: u8,
+}
+
+impl TrackInformation {
+    /// Whether the track has been completely written.
+    pub fn is_complete(&self) -> bool {
+        !self.blank && !self.reserved && !self.damaged
+    }
+}
+
+/// Absolute Time In Pregroove of a recordable disc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Atip {
+    /// Start of the lead-in formatted as `MM:SS:FF`, which is how manufacturers are
+    /// identified in the published ATIP tables
+    pub manufacturer_id: String,
+    pub leadin_start: Msf,
+    /// Last possible start of the lead-out, which is the capacity of the disc
+    pub capacity: Msf,
+    /// The disc is rewritable
+    pub is_rw: bool,
+}
+
+/// The state of the drive mechanism, as reported by the MECHANISM STATUS command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MechanismStatus {
+    /// The changer failed to complete an operation
+    pub fault: bool,
+    pub changer_state: constants::ChangerState,
+    /// `None` if the drive reported a reserved state
+    pub mechanism_state: Option<constants::MechanismState>,
+    pub door_open: bool,
+    /// Slot of the disc in use, starting from 0, which is always 0 for single disc drives
+    pub current_slot: u8,
+    /// Number of slots in a changer, which is 0 for single disc drives
+    pub slots: u8,
+}
+
+/// A performance descriptor, as reported by the GET PERFORMANCE command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerfDescriptor {
+    /// The read speed the drive expects to achieve over a range of the disc, which
+    /// changes linearly from the start to the end
+    Nominal {
+        start_lba: u32,
+        /// Speed at `start_lba` in KB/s
+        start_kbps: u32,
+        end_lba: u32,
+        /// Speed at `end_lba` in KB/s
+        end_kbps: u32,
+    },
+    /// A place where reading stalls, such as a layer change or a damaged area
+    Exception {
+        lba: u32,
+        /// Expected extra delay in tenths of a millisecond
+        delay: u16,
+    },
+}
+
+/// A feature descriptor, as reported by the GET CONFIGURATION command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feature {
+    /// Feature code, such as [`constants::FEATURE_PROFILE_LIST`]
+    pub code: u16,
+    pub version: u8,
+    /// The feature is always available, regardless of the loaded media
+    pub persistent: bool,
+    /// The feature is available with the currently loaded media
+    pub current: bool,
+    /// Feature dependent data following the descriptor header
+    pub data: Vec<u8>,
 }
 
-struct VolCtl {
+impl Feature {
+    /// The profiles listed by a Profile List feature, and whether each one is current.
+    ///
+    /// Returns an empty list for any other feature.
+    pub fn profiles(&self) -> Vec<(u16, bool)> {
+        if self.code != constants::FEATURE_PROFILE_LIST {
+            return Vec::new()
+        }
+
+        self.data
+            .chunks_exact(4)
+            .map(|p| (u16::from_be_bytes([p[0], p[1]]), p[2] & 0x01 != 0))
+            .collect()
+    }
+}
+
+/// An event reported by the GET EVENT STATUS NOTIFICATION command.
+///
+/// Other than for media events, the event code of the notification class is
+/// passed through as is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriveEvent {
+    /// No event is waiting in any of the requested classes
+    NoEvent,
+    OperationalChange(u8),
+    PowerManagement(u8),
+    ExternalRequest(u8),
+    Media {
+        event: MediaEvent,
+        tray_open: bool,
+        media_present: bool,
+    },
+    DeviceBusy(u8),
+}
+
+/// Media events reported in a [`DriveEvent::Media`] notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaEvent {
+    NoChange,
+    /// The user pressed the eject button
+    EjectRequest,
+    NewMedia,
+    MediaRemoval,
+    MediaChanged,
+    BackgroundFormatCompleted,
+    BackgroundFormatRestarted,
+    Unknown(u8),
+}
 
+impl From<u8> for MediaEvent {
+    fn from(code: u8) -> Self {
+        match code {
+            0 => MediaEvent::NoChange,
+            1 => MediaEvent::EjectRequest,
+            2 => MediaEvent::NewMedia,
+            3 => MediaEvent::MediaRemoval,
+            4 => MediaEvent::MediaChanged,
+            5 => MediaEvent::BackgroundFormatCompleted,
+            6 => MediaEvent::BackgroundFormatRestarted,
+            c => MediaEvent::Unknown(c),
+        }
+    }
+}
+
+/// Statistics gathered by [`crate::CDRom::read_audio_verified`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerifyStats {
+    /// Frames that were read and verified
+    pub frames: usize,
+    /// Frames where the first two reads didn't agree
+    pub mismatched_frames: usize,
+    /// Extra single frame reads needed before the reads agreed
+    pub rereads: usize,
+}
+
+/// Output volume of each audio channel, from `0` to `255`.
+///
+/// This struct is used by [`crate::constants::Operation::VolumeControl`] and
+/// [`crate::constants::Operation::VolumeRead`]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Volume {
+    pub channel0: u8,
+    pub channel1: u8,
+    pub channel2: u8,
+    pub channel3: u8,
 }
 
 #[repr(C)]
@@ -188,6 +641,7 @@ impl Default for _SubChannel {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SubChannel {
     pub audiostatus: u8,
     pub adr: u8,
@@ -197,3 +651,332 @@ pub struct SubChannel {
     pub absaddr: Addr,
     pub reladdr: Addr,
 }
+
+impl SubChannel {
+    /// Decode the track, index, and MSF addresses from binary coded decimal.
+    ///
+    /// Most drives report plain binary, but some older ones pass through the BCD
+    /// values stored on the disc. LBA addresses are left as they are.
+    pub fn decode_bcd(&self) -> SubChannel {
+        let decode_addr = |addr: Addr| match addr {
+            Addr::Msf(msf) => Addr::Msf(Msf {
+                minute: from_bcd(msf.minute),
+                second: from_bcd(msf.second),
+                frame: from_bcd(msf.frame),
+            }),
+            lba => lba,
+        };
+
+        SubChannel {
+            trk: if self.trk == constants::CDROM_LEADOUT { self.trk } else { from_bcd(self.trk) },
+            ind: from_bcd(self.ind),
+            absaddr: decode_addr(self.absaddr),
+            reladdr: decode_addr(self.reladdr),
+            ..*self
+        }
+    }
+}
+
+/// The raw Q subchannel of a single frame, as stored on the disc.
+///
+/// Unlike [`SubChannel`], this includes the CRC, so reads that were corrupted on the
+/// way from the disc can be rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QSubChannel(pub [u8; 12]);
+
+impl QSubChannel {
+    /// Extract the Q subchannel from 96 bytes of raw P-W subchannel data, where it is
+    /// stored in bit 6 of every byte.
+    pub fn from_pw(raw: &[u8]) -> Option<Self> {
+        let raw = raw.get(..constants::CD_FRAMESIZE_SUB as usize)?;
+
+        let mut q = [0u8; 12];
+        for (i, byte) in raw.iter().enumerate() {
+            q[i / 8] |= ((byte >> 6) & 0x01) << (7 - i % 8);
+        }
+
+        Some(Self(q))
+    }
+
+    /// Whether the CRC matches the rest of the data.
+    pub fn is_valid(&self) -> bool {
+        // CRC-16/CCITT over the first 10 bytes, stored inverted
+        let mut crc: u16 = 0;
+        for byte in &self.0[..10] {
+            crc ^= (*byte as u16) << 8;
+            for _ in 0..8 {
+                crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+            }
+        }
+
+        !crc == u16::from_be_bytes([self.0[10], self.0[11]])
+    }
+
+    /// The position the Q subchannel holds, with every field decoded from BCD.
+    ///
+    /// Returns `None` if the subchannel holds something other than a position, such
+    /// as the MCN or an ISRC. The audio status is always
+    /// [`AudioStates::NoStatus`](constants::AudioStates::NoStatus).
+    pub fn position(&self) -> Option<SubChannel> {
+        let q = &self.0;
+        if q[0] & 0x0F != 0x01 {
+            return None
+        }
+
+        let msf = |i: usize| Addr::Msf(Msf { minute: q[i], second: q[i + 1], frame: q[i + 2] });
+
+        let position = SubChannel {
+            audiostatus: constants::AudioStates::NoStatus as u8,
+            adr: q[0] & 0x0F,
+            ctrl: q[0] >> 4,
+            trk: q[1],
+            ind: q[2],
+            absaddr: msf(7),
+            reladdr: msf(3),
+        };
+
+        Some(position.decode_bcd())
+    }
+}
+
+/// Decode a binary coded decimal byte, such as `0x42` for 42.
+pub fn from_bcd(value: u8) -> u8 {
+    (value >> 4) * 10 + (value & 0x0F)
+}
+
+/// Encode a number below 100 as binary coded decimal, such as 42 as `0x42`.
+pub fn to_bcd(value: u8) -> u8 {
+    ((value / 10) << 4) | (value % 10)
+}
+
+/// A set of options controlling how the kernel driver handles a drive, as used by
+/// [`CDRom::set_options`](crate::CDRom::set_options).
+///
+/// Options can be combined with `|`, such as `CdOptions::AUTO_CLOSE | CdOptions::LOCK`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CdOptions(pub i32);
+
+impl CdOptions {
+    /// Close the tray when the drive is opened
+    pub const AUTO_CLOSE: Self = Self(0x01);
+    /// Eject the disc when the last process using the drive closes it
+    pub const AUTO_EJECT: Self = Self(0x02);
+    /// Take the flags the drive was opened with into account, such as `O_NONBLOCK`
+    pub const USE_FFLAGS: Self = Self(0x04);
+    /// Lock the door while the drive is open
+    pub const LOCK: Self = Self(0x08);
+    /// Refuse to open a disc without data tracks, unless `O_NONBLOCK` is used
+    pub const CHECK_TYPE: Self = Self(0x10);
+
+    /// Check whether every option in `options` is set.
+    pub fn contains(&self, options: CdOptions) -> bool {
+        self.0 & options.0 == options.0
+    }
+}
+
+impl BitOr for CdOptions {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for CdOptions {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// The set of [`Capability`] flags a drive reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities(pub u32);
+
+impl Capabilities {
+    /// Check whether the drive reports a given capability.
+    pub fn has(&self, capability: Capability) -> bool {
+        self.0 & capability as u32 != 0
+    }
+
+    /// Whether the drive can play audio through its own output.
+    pub fn can_play_audio(&self) -> bool {
+        self.has(Capability::PlayAudio)
+    }
+
+    /// Whether the drive can open its tray.
+    pub fn can_eject(&self) -> bool {
+        self.has(Capability::OpenTray)
+    }
+
+    /// Whether the drive can close its tray.
+    pub fn can_close_tray(&self) -> bool {
+        self.has(Capability::CloseTray)
+    }
+
+    /// Whether the drive's door can be locked.
+    pub fn can_lock(&self) -> bool {
+        self.has(Capability::Lock)
+    }
+
+    /// Whether the drive can read CD-R media.
+    pub fn can_read_cdr(&self) -> bool {
+        self.has(Capability::CdR)
+    }
+
+    /// Whether the drive can read CD-RW media.
+    pub fn can_read_cdrw(&self) -> bool {
+        self.has(Capability::CdRW)
+    }
+
+    /// Whether the drive's read speed can be changed.
+    pub fn can_change_speed(&self) -> bool {
+        self.has(Capability::SelectSpeed)
+    }
+
+    /// Whether the drive can report the Media Catalog Number.
+    pub fn can_read_mcn(&self) -> bool {
+        self.has(Capability::Mcn)
+    }
+
+    /// Whether the drive accepts generic packet commands.
+    pub fn can_send_packets(&self) -> bool {
+        self.has(Capability::GenericPacket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(msf: Msf) -> (u8, u8, u8) {
+        (msf.minute, msf.second, msf.frame)
+    }
+
+    #[test]
+    fn pregap_maps_to_the_first_two_seconds() {
+        assert_eq!(fields(Msf::from_lba(-150)), (0, 0, 0));
+        assert_eq!(fields(Msf::from_lba(-1)), (0, 1, 74));
+        assert_eq!(fields(Msf::from_lba(0)), (0, 2, 0));
+
+        for lba in -150..=0 {
+            assert_eq!(Msf::from_lba(lba).to_lba(), lba);
+        }
+    }
+
+    #[test]
+    fn leadin_maps_to_ninety_minutes_and_back() {
+        assert_eq!(fields(Msf::from_lba(-151)), (99, 59, 74));
+        assert_eq!(fields(Msf::from_lba(-45150)), (90, 0, 0));
+
+        assert_eq!(Msf { minute: 90, second: 0, frame: 0 }.to_lba(), -45150);
+        assert_eq!(Msf { minute: 99, second: 59, frame: 74 }.to_lba(), -151);
+        for lba in [-45150, -45149, -10000, -152, -151] {
+            assert_eq!(Msf::from_lba(lba).to_lba(), lba);
+        }
+    }
+
+    #[test]
+    fn invalid_rejects_out_of_range_fields_and_the_pregap() {
+        let msf = |minute, second, frame| Msf { minute, second, frame };
+
+        assert!(!msf(10, 59, 0).invalid());
+        assert!(msf(10, 60, 0).invalid());
+        assert!(!msf(10, 0, 74).invalid());
+        assert!(msf(10, 0, 75).invalid());
+        assert!(msf(0, 1, 74).invalid());
+        assert!(!msf(0, 2, 0).invalid());
+    }
+
+    #[test]
+    fn to_lba_handles_the_largest_minutes() {
+        // Counted as lead-in addresses, so 450150 frames come off
+        assert_eq!(Msf { minute: 255, second: 59, frame: 74 }.to_lba(), 1_151_999 - 450_150);
+        assert_eq!(Msf { minute: 255, second: 255, frame: 255 }.to_lba(), 1_166_880 - 450_150);
+    }
+
+    #[test]
+    fn frames_between_is_negative_going_backwards() {
+        let a = Msf { minute: 1, second: 0, frame: 0 };
+        let b = Msf { minute: 1, second: 1, frame: 5 };
+
+        assert_eq!(a.frames_between(&b), 80);
+        assert_eq!(b.frames_between(&a), -80);
+        assert_eq!(a.frames_between(&a), 0);
+        assert_eq!(b - a, 80);
+        assert_eq!(a - b, -80);
+
+        // Across the pregap and into the lead-in
+        assert_eq!(Msf::from_lba(-1).frames_between(&Msf::from_lba(0)), 1);
+        assert_eq!(Msf::from_lba(-151).frames_between(&Msf::from_lba(-150)), 1);
+    }
+
+    #[test]
+    fn add_frames_moves_both_ways() {
+        let msf = Msf { minute: 1, second: 0, frame: 0 };
+
+        assert_eq!(fields(msf.add_frames(80)), (1, 1, 5));
+        assert_eq!(fields(msf.add_frames(-1)), (0, 59, 74));
+        assert_eq!(fields(msf.add_frames(0)), (1, 0, 0));
+    }
+
+    #[test]
+    fn add_frames_clamps_to_valid_addresses() {
+        let first = Msf { minute: 0, second: 2, frame: 0 };
+        let last = Msf { minute: 89, second: 59, frame: 74 };
+
+        // Never into the pregap or the lead-in
+        assert_eq!(fields(first.add_frames(-1)), (0, 2, 0));
+        assert_eq!(fields(first.add_frames(i32::MIN)), (0, 2, 0));
+        assert_eq!(fields(Msf::from_lba(-151).add_frames(1)), (0, 2, 0));
+        assert_eq!(fields(Msf::from_lba(-1).add_frames(2)), (0, 2, 1));
+
+        assert_eq!(fields(last.add_frames(1)), (89, 59, 74));
+        assert_eq!(fields(last.add_frames(i32::MAX)), (89, 59, 74));
+
+        for n in [-1000, -1, 0, 1, 1000] {
+            assert!(!first.add_frames(n).invalid());
+            assert!(!last.add_frames(n).invalid());
+        }
+    }
+
+    #[test]
+    fn lba_round_trips_through_msf() {
+        // From the start of the lead-in to the last address MSF can hold
+        for lba in (-45150..=404849).step_by(7).chain([404849]) {
+            assert_eq!(Msf::from_lba(lba).to_lba(), lba, "LBA {lba}");
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn toc_round_trips_through_json() {
+        let toc = vec![
+            TocEntry { track: 1, adr: 1, ctrl: 0x00, addr: Addr::Lba(0), datamode: 0 },
+            TocEntry { track: 2, adr: 1, ctrl: 0x00, addr: Addr::Msf(Msf { minute: 4, second: 12, frame: 33 }), datamode: 0 },
+            TocEntry { track: 3, adr: 1, ctrl: 0x04, addr: Addr::Lba(40000), datamode: 1 },
+            TocEntry { track: 0xAA, adr: 1, ctrl: 0x04, addr: Addr::Lba(52000), datamode: 1 },
+        ];
+
+        let json = serde_json::to_string(&toc).unwrap();
+        let parsed: Vec<TocEntry> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.len(), toc.len());
+        for (parsed, entry) in parsed.iter().zip(&toc) {
+            assert_eq!(parsed.track, entry.track);
+            assert_eq!(parsed.adr, entry.adr);
+            assert_eq!(parsed.ctrl, entry.ctrl);
+            assert_eq!(parsed.datamode, entry.datamode);
+            match (parsed.addr, entry.addr) {
+                (Addr::Lba(a), Addr::Lba(b)) => assert_eq!(a, b),
+                (Addr::Msf(a), Addr::Msf(b)) => {
+                    assert_eq!((a.minute, a.second, a.frame), (b.minute, b.second, b.frame))
+                }
+                (parsed, entry) => panic!("{entry:?} became {parsed:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Writing of ripped audio to WAV files.
+//!
+//! Red Book audio is always 44.1kHz, 16-bit stereo, so no configuration is needed.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::{CDRom, CDRomError};
+
+const SAMPLE_RATE: u32 = 44100;
+const CHANNELS: u16 = 2;
+const BITS_PER_SAMPLE: u16 = 16;
+
+/// Size of everything before the audio, up to and including the size of the data chunk
+const HEADER_SIZE: u64 = 44;
+
+/// Write interleaved stereo samples to `out` as a canonical RIFF WAVE file.
+///
+/// The samples are expected as returned by [`CDRom::read_audio`], which holds the
+/// drive's little-endian bytes in host byte order, so they are written out unchanged.
+///
+/// The chunk sizes are filled in after the audio has been written, and `out` is
+/// left positioned at the end of the file.
+pub fn write_wav<W: Write + Seek>(out: &mut W, samples: &[i16]) -> io::Result<()> {
+    let riff_start = write_header(out)?;
+
+    for chunk in samples.chunks(4096) {
+        let bytes: Vec<u8> = chunk.iter().flat_map(|s| s.to_ne_bytes()).collect();
+        out.write_all(&bytes)?;
+    }
+
+    finish(out, riff_start)
+}
+
+/// Write the header of a WAV file with empty chunk sizes, for audio that is streamed
+/// to `out` afterwards, such as with [`CDRom::read_audio_to`].
+///
+/// Returns the position the file starts at, which must be passed to [`finish`] once
+/// all the audio has been written.
+pub fn write_header<W: Write + Seek>(out: &mut W) -> io::Result<u64> {
+    let block_align = CHANNELS * (BITS_PER_SAMPLE / 8);
+    let byte_rate = SAMPLE_RATE * block_align as u32;
+
+    let riff_start = out.stream_position()?;
+
+    out.write_all(b"RIFF")?;
+    out.write_all(&0u32.to_le_bytes())?;
+    out.write_all(b"WAVE")?;
+
+    out.write_all(b"fmt ")?;
+    out.write_all(&16u32.to_le_bytes())?;
+    // PCM
+    out.write_all(&1u16.to_le_bytes())?;
+    out.write_all(&CHANNELS.to_le_bytes())?;
+    out.write_all(&SAMPLE_RATE.to_le_bytes())?;
+    out.write_all(&byte_rate.to_le_bytes())?;
+    out.write_all(&block_align.to_le_bytes())?;
+    out.write_all(&BITS_PER_SAMPLE.to_le_bytes())?;
+
+    out.write_all(b"data")?;
+    out.write_all(&0u32.to_le_bytes())?;
+
+    Ok(riff_start)
+}
+
+/// Fill in the chunk sizes of a WAV file started with [`write_header`] at `riff_start`,
+/// once `out` is positioned at the end of the audio.
+///
+/// `out` is left positioned at the end of the file.
+pub fn finish<W: Write + Seek>(out: &mut W, riff_start: u64) -> io::Result<()> {
+    let end = out.stream_position()?;
+
+    let data_size = (end - riff_start - HEADER_SIZE) as u32;
+    let riff_size = (end - riff_start - 8) as u32;
+
+    out.seek(SeekFrom::Start(riff_start + 4))?;
+    out.write_all(&riff_size.to_le_bytes())?;
+    out.seek(SeekFrom::Start(riff_start + HEADER_SIZE - 4))?;
+    out.write_all(&data_size.to_le_bytes())?;
+    out.seek(SeekFrom::Start(end))?;
+
+    Ok(())
+}
+
+impl CDRom {
+    /// Rip every audio track on the disc to its own WAV file in `dir`, named
+    /// `track01.wav`, `track02.wav`, and so on. Data tracks are skipped.
+    ///
+    /// Returns the paths of the files written, in track order.
+    pub fn rip_all_tracks(&mut self, dir: &Path) -> Result<Vec<PathBuf>, CDRomError> {
+        let tracks = self.tracks()?;
+
+        let mut paths = Vec::new();
+        for track in tracks.iter().filter(|t| t.is_audio) {
+            let path = dir.join(format!("track{:02}.wav", track.number));
+            let mut out = BufWriter::new(File::create(&path)?);
+
+            let riff_start = write_header(&mut out)?;
+            self.read_audio_to(track.start, track.length_frames as usize, &mut out)?;
+            finish(&mut out, riff_start)?;
+            out.flush()?;
+
+            paths.push(path);
+        }
+
+        Ok(paths)
+    }
+}